@@ -0,0 +1,30 @@
+// Release builds must carry a usable release public key; a launcher without one
+// refuses every update and could never install the miner on a fresh rig. Debug
+// builds may leave it out so the launcher can be built and tested locally.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::VerifyingKey;
+use std::env;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=NOCKPOOL_RELEASE_PUBLIC_KEY");
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if env::var("PROFILE").as_deref() != Ok("release") {
+        return;
+    }
+
+    let key = env::var("NOCKPOOL_RELEASE_PUBLIC_KEY").unwrap_or_default();
+    if key.trim().is_empty() {
+        panic!("NOCKPOOL_RELEASE_PUBLIC_KEY must be set for release builds");
+    }
+
+    let bytes: [u8; 32] = STANDARD
+        .decode(key.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .unwrap_or_else(|| panic!("NOCKPOOL_RELEASE_PUBLIC_KEY is not a base64 Ed25519 public key"));
+    if let Err(e) = VerifyingKey::from_bytes(&bytes) {
+        panic!("NOCKPOOL_RELEASE_PUBLIC_KEY is not a valid Ed25519 public key: {}", e);
+    }
+}
@@ -0,0 +1,291 @@
+// This module unpacks release assets. The miner binary may be published as a
+// raw executable or inside a zip, tar.gz or tar.zst archive on any platform.
+// The format is taken from the asset's file extension, falling back to the
+// file's magic bytes, and every entry is checked so an archive can't write
+// outside the directory it's extracted into.
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use std::fs;
+use std::io::{self, Read, Seek};
+use std::path::{Component, Path, PathBuf};
+use tar::EntryType;
+use zip::ZipArchive;
+
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Raw,
+    Zip,
+    TarGz,
+    TarZst,
+}
+
+impl ArchiveKind {
+    /// Guesses the format from an asset name, if its extension gives it away.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            Some(ArchiveKind::TarZst)
+        } else {
+            None
+        }
+    }
+
+    /// Detects the format of `path`, downloaded as the asset called `name`.
+    pub fn detect(path: &Path, name: &str) -> Result<Self> {
+        if let Some(kind) = Self::from_name(name) {
+            return Ok(kind);
+        }
+
+        let mut magic = [0u8; 4];
+        let mut file = fs::File::open(path)?;
+        let read = file.read(&mut magic)?;
+        let magic = &magic[..read];
+
+        Ok(if magic.starts_with(ZIP_MAGIC) {
+            ArchiveKind::Zip
+        } else if magic.starts_with(GZIP_MAGIC) {
+            ArchiveKind::TarGz
+        } else if magic.starts_with(ZSTD_MAGIC) {
+            ArchiveKind::TarZst
+        } else {
+            ArchiveKind::Raw
+        })
+    }
+}
+
+/// Extracts the archive at `path` into `dest`. Raw files are not handled here.
+pub fn extract(path: &Path, kind: ArchiveKind, dest: &Path) -> Result<()> {
+    let file = fs::File::open(path)?;
+    match kind {
+        ArchiveKind::Raw => Err(anyhow!("{} is not an archive", path.display())),
+        ArchiveKind::Zip => extract_zip(file, dest),
+        ArchiveKind::TarGz => extract_tar(GzDecoder::new(file), dest),
+        ArchiveKind::TarZst => extract_tar(zstd::Decoder::new(file)?, dest),
+    }
+    .with_context(|| format!("Failed to extract {}", path.display()))
+}
+
+fn extract_zip<R: Read + Seek>(reader: R, dest: &Path) -> Result<()> {
+    let mut archive = ZipArchive::new(reader)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let relative = entry
+            .enclosed_name()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| anyhow!("Archive entry '{}' escapes the install directory", entry.name()))?;
+        let out_path = dest.join(relative);
+
+        // Same as for tar: links could point outside the install directory
+        if entry.unix_mode().is_some_and(|mode| mode & S_IFMT == S_IFLNK) {
+            return Err(anyhow!("Unsupported archive entry '{}' (symlink)", entry.name()));
+        }
+
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path)?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut fs::File::create(&out_path)?)?;
+
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&out_path, fs::Permissions::from_mode(mode & 0o777))?;
+        }
+    }
+    Ok(())
+}
+
+fn extract_tar<R: Read>(reader: R, dest: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let relative = enclosed_path(&path)
+            .ok_or_else(|| anyhow!("Archive entry '{}' escapes the install directory", path.display()))?;
+
+        match entry.header().entry_type() {
+            EntryType::Directory => fs::create_dir_all(dest.join(relative))?,
+            EntryType::Regular | EntryType::Continuous => {
+                let out_path = dest.join(relative);
+                if let Some(parent) = out_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                entry.unpack(&out_path)?;
+            }
+            EntryType::XGlobalHeader | EntryType::XHeader => {}
+            // Links could point outside the install directory, and nothing we ship needs them
+            other => return Err(anyhow!("Unsupported archive entry '{}' ({:?})", path.display(), other)),
+        }
+    }
+    Ok(())
+}
+
+/// A single path component that can't escape the directory it's joined to.
+pub fn is_plain_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+}
+
+/// `path` with any leading `./` removed, or `None` if it is absolute or climbs
+/// out with `..`.
+fn enclosed_path(path: &Path) -> Option<PathBuf> {
+    let mut enclosed = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => enclosed.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(enclosed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    /// A fresh `dest` directory inside its own scratch directory, so tests can
+    /// check nothing was written next to it.
+    fn scratch_dest(test: &str) -> (PathBuf, PathBuf) {
+        let base = std::env::temp_dir().join(format!("miner-launcher-archive-{}-{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&base);
+        let dest = base.join("dest");
+        fs::create_dir_all(&dest).unwrap();
+        (base, dest)
+    }
+
+    /// A tar archive with one entry, written with a raw header so paths that
+    /// `tar::Builder` itself refuses can be tested.
+    fn tar_entry(name: &str, entry_type: EntryType, link_name: Option<&str>, data: &[u8]) -> Vec<u8> {
+        let mut header = tar::Header::new_gnu();
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        if let Some(link_name) = link_name {
+            header.as_old_mut().linkname[..link_name.len()].copy_from_slice(link_name.as_bytes());
+        }
+        header.set_entry_type(entry_type);
+        header.set_size(data.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+
+        let mut builder = tar::Builder::new(Vec::new());
+        builder.append(&header, data).unwrap();
+        builder.into_inner().unwrap()
+    }
+
+    fn zip_entry(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file(name, FileOptions::default()).unwrap();
+        zip.write_all(data).unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn enclosed_path_strips_cur_dir_and_rejects_escapes() {
+        assert_eq!(enclosed_path(Path::new("./bin/miner")), Some(PathBuf::from("bin/miner")));
+        assert_eq!(enclosed_path(Path::new("../miner")), None);
+        assert_eq!(enclosed_path(Path::new("bin/../../miner")), None);
+        assert_eq!(enclosed_path(Path::new("/etc/passwd")), None);
+    }
+
+    #[test]
+    fn tar_extracts_cur_dir_prefixed_entry() {
+        let (base, dest) = scratch_dest("tar-ok");
+        let archive = tar_entry("./nockpool-miner", EntryType::Regular, None, b"miner");
+
+        extract_tar(Cursor::new(archive), &dest).unwrap();
+
+        assert_eq!(fs::read(dest.join("nockpool-miner")).unwrap(), b"miner");
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn tar_rejects_parent_dir_entry() {
+        let (base, dest) = scratch_dest("tar-parent");
+        let archive = tar_entry("../escaped", EntryType::Regular, None, b"evil");
+
+        assert!(extract_tar(Cursor::new(archive), &dest).is_err());
+        assert!(!base.join("escaped").exists());
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn tar_rejects_absolute_entry() {
+        let (base, dest) = scratch_dest("tar-absolute");
+        let target = base.join("absolute");
+        let archive = tar_entry(target.to_str().unwrap(), EntryType::Regular, None, b"evil");
+
+        assert!(extract_tar(Cursor::new(archive), &dest).is_err());
+        assert!(!target.exists());
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn tar_rejects_symlink_entry() {
+        let (base, dest) = scratch_dest("tar-symlink");
+        let archive = tar_entry("link", EntryType::Symlink, Some("../.."), b"");
+
+        assert!(extract_tar(Cursor::new(archive), &dest).is_err());
+        assert!(fs::symlink_metadata(dest.join("link")).is_err());
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn zip_extracts_cur_dir_prefixed_entry() {
+        let (base, dest) = scratch_dest("zip-ok");
+        let archive = zip_entry("./nockpool-miner", b"miner");
+
+        extract_zip(Cursor::new(archive), &dest).unwrap();
+
+        assert_eq!(fs::read(dest.join("nockpool-miner")).unwrap(), b"miner");
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn zip_rejects_parent_dir_entry() {
+        let (base, dest) = scratch_dest("zip-parent");
+        let archive = zip_entry("../escaped", b"evil");
+
+        assert!(extract_zip(Cursor::new(archive), &dest).is_err());
+        assert!(!base.join("escaped").exists());
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn zip_rejects_absolute_entry() {
+        let (base, dest) = scratch_dest("zip-absolute");
+        let target = base.join("absolute");
+        let archive = zip_entry(target.to_str().unwrap(), b"evil");
+
+        assert!(extract_zip(Cursor::new(archive), &dest).is_err());
+        assert!(!target.exists());
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn zip_rejects_symlink_entry() {
+        let (base, dest) = scratch_dest("zip-symlink");
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.add_symlink("link", "../..", FileOptions::default()).unwrap();
+        let archive = zip.finish().unwrap().into_inner();
+
+        assert!(extract_zip(Cursor::new(archive), &dest).is_err());
+        assert!(fs::symlink_metadata(dest.join("link")).is_err());
+        fs::remove_dir_all(base).unwrap();
+    }
+}
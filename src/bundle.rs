@@ -0,0 +1,237 @@
+// This module reads and writes offline install bundles for rigs that can't
+// reach the update server. A bundle is a directory or a zip, tar.gz or tar.zst
+// archive holding the miner binary, its optional .so and .jam files, and a
+// `manifest.json` that names the version and lists a SHA-256 checksum for
+// every file. A binary published as an archive is bundled as that archive, so
+// the bundle carries exactly what the signed release vouches for.
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::archive::{self, is_plain_name, ArchiveKind};
+use crate::download;
+use crate::SignedRelease;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub version: String,
+    pub bin_name: String,
+    pub so_file: Option<String>,
+    pub jam_file: Option<String>,
+    /// Release asset `bin_name` is extracted from, when the binary was published
+    /// as an archive. The bundle then holds this asset instead of the binary.
+    #[serde(default)]
+    pub bin_asset: Option<String>,
+    /// File name to "sha256:<hex>" digest for every file in the bundle.
+    pub files: BTreeMap<String, String>,
+    /// The signed release response this version was installed from, if known.
+    pub release: Option<SignedRelease>,
+}
+
+impl BundleManifest {
+    fn validate(&self) -> Result<()> {
+        if !is_plain_name(&self.version) {
+            return Err(anyhow!("Bundle manifest has an invalid version '{}'", self.version));
+        }
+
+        for name in self.files.keys() {
+            if !is_plain_name(name) {
+                return Err(anyhow!("Bundle manifest lists an invalid file name '{}'", name));
+            }
+        }
+
+        let bin_file = self.bin_asset.as_ref().unwrap_or(&self.bin_name);
+        let expected = [Some(bin_file), self.so_file.as_ref(), self.jam_file.as_ref()];
+        for name in expected.into_iter().flatten() {
+            if !self.files.contains_key(name) {
+                return Err(anyhow!("Bundle manifest has no checksum for {}", name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Copies or extracts the files listed in `bundle`'s manifest into `dest` and
+/// checks each against its checksum. Anything else in the bundle, including
+/// the manifest itself, is left out of `dest`.
+pub fn unpack(bundle: &Path, dest: &Path) -> Result<BundleManifest> {
+    let manifest = if bundle.is_dir() {
+        let manifest = read_manifest(bundle)?;
+        for name in manifest.files.keys() {
+            let path = bundle.join(name);
+            if !path.is_file() {
+                return Err(anyhow!("Bundle is missing {}", name));
+            }
+            fs::copy(&path, dest.join(name))?;
+        }
+        manifest
+    } else {
+        let name = bundle.file_name().unwrap_or_default().to_string_lossy();
+        let kind = ArchiveKind::detect(bundle, &name)
+            .with_context(|| format!("Failed to open bundle {}", bundle.display()))?;
+        if kind == ArchiveKind::Raw {
+            return Err(anyhow!("{} is not a directory or archive", bundle.display()));
+        }
+        archive::extract(bundle, kind, dest)?;
+
+        let manifest = read_manifest(dest)?;
+        for entry in fs::read_dir(dest)? {
+            let entry = entry?;
+            let listed = entry.file_name().to_str().is_some_and(|name| manifest.files.contains_key(name));
+            if listed && entry.file_type()?.is_file() {
+                continue;
+            }
+            if entry.file_type()?.is_dir() {
+                fs::remove_dir_all(entry.path())?;
+            } else {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        manifest
+    };
+
+    for (name, digest) in &manifest.files {
+        let path = dest.join(name);
+        if !path.is_file() {
+            return Err(anyhow!("Bundle is missing {}", name));
+        }
+        download::verify_sha256(&path, name, digest)?;
+    }
+
+    Ok(manifest)
+}
+
+fn read_manifest(dir: &Path) -> Result<BundleManifest> {
+    let contents = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .context("Bundle has no manifest.json")?;
+    let manifest: BundleManifest =
+        serde_json::from_str(&contents).context("Bundle manifest.json is invalid")?;
+    manifest.validate()?;
+    Ok(manifest)
+}
+
+/// SHA-256 digests of the regular files directly inside `dir`.
+pub fn checksums(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue;
+        }
+        let name = entry
+            .file_name()
+            .into_string()
+            .map_err(|name| anyhow!("Unsupported file name {:?}", name))?;
+        if name.starts_with('.') {
+            continue;
+        }
+        let digest = download::sha256_file(&entry.path())?;
+        files.insert(name, format!("sha256:{}", digest));
+    }
+    Ok(files)
+}
+
+/// Packs the files listed in `manifest` from `dir`, plus the manifest itself,
+/// into a zip archive at `output`.
+pub fn write_zip(dir: &Path, manifest: &BundleManifest, output: &Path) -> Result<()> {
+    let mut tmp_name = output.as_os_str().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = Path::new(&tmp_name);
+
+    let mut zip = ZipWriter::new(fs::File::create(tmp_path)?);
+    for name in manifest.files.keys() {
+        let path = dir.join(name);
+        let mut options = FileOptions::default();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            options = options.unix_permissions(fs::metadata(&path)?.permissions().mode());
+        }
+        zip.start_file(name.as_str(), options)?;
+        std::io::copy(&mut fs::File::open(&path)?, &mut zip)?;
+    }
+
+    zip.start_file(MANIFEST_FILE, FileOptions::default())?;
+    zip.write_all(serde_json::to_string_pretty(manifest)?.as_bytes())?;
+    zip.finish()?.sync_all()?;
+
+    fs::rename(tmp_path, output)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("miner-launcher-bundle-{}-{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn manifest(dir: &Path) -> BundleManifest {
+        BundleManifest {
+            version: "1.2.3".to_string(),
+            bin_name: "nockpool-miner".to_string(),
+            so_file: None,
+            jam_file: None,
+            bin_asset: None,
+            files: checksums(dir).unwrap(),
+            release: None,
+        }
+    }
+
+    #[test]
+    fn unpack_directory_skips_unlisted_files() {
+        let base = scratch_dir("directory");
+        let (bundle, dest) = (base.join("bundle"), base.join("dest"));
+        fs::create_dir_all(&bundle).unwrap();
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(bundle.join("nockpool-miner"), b"miner").unwrap();
+        let manifest = manifest(&bundle);
+        fs::write(bundle.join(MANIFEST_FILE), serde_json::to_string(&manifest).unwrap()).unwrap();
+        fs::write(bundle.join("extra.so"), b"unlisted").unwrap();
+
+        unpack(&bundle, &dest).unwrap();
+        let names: Vec<_> = fs::read_dir(&dest).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(names, ["nockpool-miner"]);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn unpack_archive_removes_unlisted_files() {
+        let base = scratch_dir("archive");
+        let (files, dest) = (base.join("files"), base.join("dest"));
+        fs::create_dir_all(&files).unwrap();
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(files.join("nockpool-miner"), b"miner").unwrap();
+        let manifest = manifest(&files);
+        fs::write(files.join("extra.so"), b"unlisted").unwrap();
+
+        // write_zip only packs listed files, so add the extra one by hand
+        let bundle = base.join("bundle.zip");
+        write_zip(&files, &manifest, &bundle).unwrap();
+        let mut zip = ZipWriter::new_append(fs::OpenOptions::new().read(true).write(true).open(&bundle).unwrap()).unwrap();
+        zip.start_file("extra.so", FileOptions::default()).unwrap();
+        zip.write_all(b"unlisted").unwrap();
+        zip.finish().unwrap();
+
+        unpack(&bundle, &dest).unwrap();
+        let names: Vec<_> = fs::read_dir(&dest).unwrap().map(|entry| entry.unwrap().file_name()).collect();
+        assert_eq!(names, ["nockpool-miner"]);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
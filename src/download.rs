@@ -0,0 +1,211 @@
+// This module downloads release assets. Files are streamed to a `.part` file
+// on disk rather than buffered in memory, and an interrupted download picks
+// up where it left off with an HTTP Range request, including across launcher
+// restarts. Finished files are checked against Content-Length and, when the
+// release publishes one, their SHA-256 digest.
+
+use anyhow::{anyhow, Result};
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::{Client, StatusCode};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+use crate::error::UpdateError;
+use crate::retry;
+use crate::tracer::{CombinedProgress, DownloadProgress};
+
+pub const DOWNLOAD_ATTEMPTS: u32 = 8;
+
+/// Downloads `url` to `dest`, resuming from `dest.part` if an earlier attempt
+/// was interrupted. `dest` only appears once the whole file has arrived.
+/// Progress goes to `combined` when the file is one of several downloads, and
+/// an attempt that receives nothing for `read_timeout` is abandoned and retried.
+pub async fn download_to_file(
+    client: &Client,
+    url: &str,
+    dest: &Path,
+    attempts: u32,
+    read_timeout: Duration,
+    combined: Option<&Arc<CombinedProgress>>,
+) -> Result<()> {
+    let name = dest
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| url.to_string());
+
+    if let Some(meta) = fs::metadata(dest).ok().filter(|meta| meta.is_file()) {
+        // Finished by an earlier run that didn't get to install it
+        if let Some(combined) = combined {
+            combined.update(&name, meta.len());
+        }
+        return Ok(());
+    }
+
+    let part_path = part_path(dest);
+
+    retry::with_backoff(&format!("Download of {}", name), attempts, || {
+        download_attempt(client, url, &part_path, &name, read_timeout, combined)
+    })
+    .await?;
+
+    fs::rename(&part_path, dest)?;
+    Ok(())
+}
+
+async fn download_attempt(
+    client: &Client,
+    url: &str,
+    part_path: &Path,
+    name: &str,
+    read_timeout: Duration,
+    combined: Option<&Arc<CombinedProgress>>,
+) -> Result<()> {
+    let existing = fs::metadata(part_path).map(|meta| meta.len()).unwrap_or(0);
+
+    let mut request = client.get(url);
+    if existing > 0 {
+        request = request.header(RANGE, format!("bytes={}-", existing));
+    }
+    let mut response = tokio::time::timeout(read_timeout, request.send())
+        .await
+        .map_err(|_| UpdateError::Interrupted(format!("no response for {} in {}s", name, read_timeout.as_secs())))?
+        .map_err(UpdateError::Network)?;
+
+    let (offset, expected_total) = match response.status() {
+        StatusCode::PARTIAL_CONTENT => {
+            let total = content_range_total(&response)
+                .or_else(|| response.content_length().map(|len| existing + len));
+            info!("Resuming download of {} at {} bytes", name, existing);
+            (existing, total)
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            // Whatever we have doesn't line up with the file on the server anymore
+            fs::remove_file(part_path)?;
+            return Err(UpdateError::Interrupted(format!("server rejected resuming {}, starting over", name)).into());
+        }
+        status if UpdateError::is_transient_status(status) => {
+            return Err(UpdateError::Server(status).into());
+        }
+        _ => {
+            // A plain 200 means the server ignored the range, so start from zero
+            let response_len = response.content_length();
+            response = response.error_for_status()?;
+            (0, response_len)
+        }
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(part_path)
+        .await?;
+
+    let mut progress = DownloadProgress::new(name, expected_total, offset, combined.cloned());
+    loop {
+        let chunk = tokio::time::timeout(read_timeout, response.chunk())
+            .await
+            .map_err(|_| {
+                UpdateError::Interrupted(format!("no data received for {} in {}s", name, read_timeout.as_secs()))
+            })?
+            .map_err(UpdateError::Network)?;
+        let Some(chunk) = chunk else {
+            break;
+        };
+        file.write_all(&chunk).await?;
+        progress.advance(chunk.len() as u64);
+    }
+    file.flush().await?;
+    file.sync_all().await?;
+    drop(file);
+
+    let downloaded = fs::metadata(part_path)?.len();
+    match expected_total {
+        Some(total) if downloaded < total => Err(UpdateError::Interrupted(format!(
+            "{} is incomplete: got {} of {} bytes",
+            name, downloaded, total
+        ))
+        .into()),
+        Some(total) if downloaded > total => {
+            fs::remove_file(part_path)?;
+            Err(anyhow!(
+                "{} is larger than announced: got {} of {} bytes",
+                name,
+                downloaded,
+                total
+            ))
+        }
+        _ => {
+            progress.finish();
+            Ok(())
+        }
+    }
+}
+
+/// Total length from a `Content-Range: bytes start-end/total` header.
+fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Removes `dest` and any partial download of it.
+pub fn discard(dest: &Path) {
+    let _ = fs::remove_file(dest);
+    let _ = fs::remove_file(part_path(dest));
+}
+
+pub fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_os_string();
+    name.push(".part");
+    PathBuf::from(name)
+}
+
+/// Checks a downloaded file against a `sha256:<hex>` or bare hex digest. A file
+/// that doesn't match is deleted so the next attempt downloads it again.
+pub fn verify_sha256(path: &Path, name: &str, expected: &str) -> Result<()> {
+    let expected = expected.strip_prefix("sha256:").unwrap_or(expected);
+    let actual = sha256_file(path)?;
+
+    if !actual.eq_ignore_ascii_case(expected) {
+        let len = fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+        let _ = fs::remove_file(path);
+        return Err(anyhow!(
+            "Checksum mismatch for {}: expected {}, got {} ({} bytes downloaded)",
+            name,
+            expected,
+            actual,
+            len
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
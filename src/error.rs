@@ -0,0 +1,71 @@
+// This module defines the errors the update path can run into, so callers can
+// tell a flaky connection or an overloaded server, which are worth retrying,
+// apart from the server telling us this system isn't supported.
+
+use reqwest::StatusCode;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum UpdateError {
+    /// The request never got a response, or the connection dropped mid-transfer.
+    Network(reqwest::Error),
+    /// The server answered with a 5xx or 429 status.
+    Server(StatusCode),
+    /// A download ended before all of its bytes arrived.
+    Interrupted(String),
+    /// The server has no build for this system.
+    Unsupported,
+    /// The filesystem holding the versions has no room for the update.
+    InsufficientSpace { needed_mb: u64, available_mb: u64 },
+    /// A new version ran its health check and exited with an error.
+    HealthCheckFailed(String),
+}
+
+impl UpdateError {
+    pub fn is_transient(&self) -> bool {
+        !matches!(
+            self,
+            UpdateError::Unsupported | UpdateError::InsufficientSpace { .. } | UpdateError::HealthCheckFailed(_)
+        )
+    }
+
+    /// Whether a response status should be retried rather than treated as final.
+    pub fn is_transient_status(status: StatusCode) -> bool {
+        status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+    }
+}
+
+impl fmt::Display for UpdateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdateError::Network(e) => write!(f, "Couldn't reach the update server: {}", e),
+            UpdateError::Server(status) => write!(f, "Update server error: {}", status),
+            UpdateError::Interrupted(reason) => write!(f, "Download interrupted: {}", reason),
+            UpdateError::Unsupported => write!(
+                f,
+                "This system isn't supported with the launcher. Please build from source."
+            ),
+            UpdateError::InsufficientSpace { needed_mb, available_mb } => write!(
+                f,
+                "Not enough disk space for the update: need {} MB, {} MB available",
+                needed_mb, available_mb
+            ),
+            UpdateError::HealthCheckFailed(reason) => write!(f, "Health check failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for UpdateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            UpdateError::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for UpdateError {
+    fn from(e: reqwest::Error) -> Self {
+        UpdateError::Network(e)
+    }
+}
@@ -0,0 +1,53 @@
+// This module builds the HTTP client every network call goes through, so
+// proxy settings, extra trusted CAs, timeouts and the User-Agent are applied
+// the same way to update checks, downloads and the LAN mirror.
+
+use anyhow::{Context, Result};
+use reqwest::{Certificate, Client, Proxy};
+use std::fs;
+use std::time::Duration;
+
+use crate::config::LauncherConfig;
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub const USER_AGENT: &str = concat!("miner-launcher/", env!("CARGO_PKG_VERSION"));
+
+pub fn client(config: &LauncherConfig) -> Result<Client> {
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(connect_timeout(config));
+
+    // Without an explicit proxy, reqwest picks up HTTPS_PROXY/ALL_PROXY from the environment
+    if let Some(proxy) = &config.proxy {
+        let proxy = Proxy::all(proxy).with_context(|| format!("Invalid proxy '{}'", proxy))?;
+        builder = builder.proxy(proxy);
+    }
+
+    for path in &config.ca_bundles {
+        let pem = fs::read(path).with_context(|| format!("Failed to read CA bundle {}", path.display()))?;
+        let certs = Certificate::from_pem_bundle(&pem)
+            .with_context(|| format!("CA bundle {} is not valid PEM", path.display()))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    builder.build().context("Failed to set up the HTTP client")
+}
+
+fn connect_timeout(config: &LauncherConfig) -> Duration {
+    config
+        .connect_timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_CONNECT_TIMEOUT)
+}
+
+/// How long a download may go without receiving any data before it is retried.
+pub fn read_timeout(config: &LauncherConfig) -> Duration {
+    config
+        .read_timeout_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_READ_TIMEOUT)
+}
@@ -1,28 +1,55 @@
+mod archive;
+mod bundle;
+mod config;
+mod download;
+mod error;
+mod http;
+mod mirror;
+mod retry;
+mod self_update;
+mod signing;
 mod tracer;
 
 use anyhow::Result;
-use reqwest::{header::USER_AGENT, Client};
+use archive::ArchiveKind;
+use chrono::{DateTime, Local, Utc};
+use config::{Channel, LauncherConfig};
+use error::UpdateError;
+use tracer::CombinedProgress;
+use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use sysinfo::{System, Disks};
 use std::env;
 use std::fs;
-use std::io::{Cursor, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::{Mutex, Notify};
-use tokio::time::{interval, Duration};
-use zip::ZipArchive;
-use tracing::info;
+use tokio::time::{sleep, Duration};
+use tracing::{info, warn};
 use directories::ProjectDirs;
+use rand::Rng;
 
 #[cfg(target_arch = "x86_64")]
 use std::arch::is_x86_feature_detected;
 
 const UPDATE_URL: &str = "https://nockpool.com/api/version";
 const UPDATE_INTERVAL: u64 = 15 * 60;
+const UPDATE_JITTER: f64 = 0.1;
+const FETCH_ATTEMPTS: u32 = 5;
+const MIRROR_DOWNLOAD_ATTEMPTS: u32 = 2;
+const STAGING_PREFIX: &str = ".staging-";
+const REPLACED_PREFIX: &str = ".replaced-";
+const REINSTALL_SEPARATOR: char = '@';
+const ROLLBACK_CRASH_THRESHOLD: usize = 3;
+const ROLLBACK_WINDOW: Duration = Duration::from_secs(10 * 60);
+const KEEP_VERSIONS: usize = 2;
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+const PRERELEASE_TAGS: &[&str] = &["alpha", "beta", "rc", "nightly"];
+const DISK_SPACE_MARGIN: u64 = 64 * 1024 * 1024;
 
 #[derive(Debug, Serialize)]
 struct GpuInfo {
@@ -115,13 +142,50 @@ struct ReleaseInfo {
     selected_binary: Option<String>,
     selected_so_file: Option<String>,
     selected_jam_file: Option<String>,
+    /// A newer launcher build, if the server wants us to update ourselves.
+    launcher: Option<self_update::LauncherRelease>,
+    #[allow(dead_code)]
     system_analysis: Option<serde_json::Value>,
 }
 
+/// A release response exactly as the server sent it, so the signature can be
+/// checked again whenever it is read back from disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct SignedRelease {
+    body: String,
+    signature: Option<String>,
+}
+
+impl ReleaseInfo {
+    fn version(&self) -> String {
+        release_version(&self.tag_name)
+    }
+}
+
+impl SignedRelease {
+    fn verify(&self) -> Result<ReleaseInfo> {
+        signing::verify_release(self.body.as_bytes(), self.signature.as_deref())?;
+        Ok(serde_json::from_str(&self.body)?)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedRelease {
+    fetched_at: DateTime<Utc>,
+    #[serde(flatten)]
+    release: SignedRelease,
+}
+
 #[derive(Debug, Deserialize)]
 struct Asset {
     name: String,
     browser_download_url: String,
+    // "sha256:<hex>" as published by GitHub, or a bare hex digest
+    #[serde(default)]
+    digest: Option<String>,
+    // Size in bytes, used to check there is room for the download
+    #[serde(default)]
+    size: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -132,27 +196,41 @@ struct PackageInfo {
     download_url: String,
     so_download_url: Option<String>,
     jam_download_url: Option<String>,
+    bin_sha256: Option<String>,
+    so_sha256: Option<String>,
+    jam_sha256: Option<String>,
+    bin_size: Option<u64>,
+    so_size: Option<u64>,
+    jam_size: Option<u64>,
     bin_name: String,
     package_name: String,
     so_file_name: Option<String>,
     jam_file_name: Option<String>,
+    base_dir: PathBuf,
     versions_dir: PathBuf,
     current_symlink: PathBuf,
+    previous_version: Option<String>,
+    updated_at: Option<Instant>,
+    crash_times: Vec<Instant>,
+    config: LauncherConfig,
+    mirror_files_url: Option<String>,
+    launcher_update: Option<self_update::LauncherRelease>,
+    launcher_exe: PathBuf,
 }
 
 impl PackageInfo {
     pub fn new() -> Result<Self> {
+        Self::with_base_dir(Self::data_dir()?)
+    }
+
+    fn with_base_dir(base_dir: PathBuf) -> Result<Self> {
         let (os_name, arch) = Self::get_device_info()?;
         let bin_name = "nockpool-miner".to_string();
 
-        let base_dir = if let Some(proj_dirs) = ProjectDirs::from("com", "swps", "nockpool-miner") {
-            proj_dirs.data_dir().to_path_buf()
-        } else {
-            return Err(anyhow::anyhow!("Could not determine application data directory"));
-        };
-
         let versions_dir = base_dir.join("versions");
         let current_symlink = base_dir.join("current");
+        let mut config = LauncherConfig::load(&base_dir)?;
+        config.apply_env()?;
 
         Ok(PackageInfo {
             os_name,
@@ -161,15 +239,49 @@ impl PackageInfo {
             download_url: String::new(),
             so_download_url: None,
             jam_download_url: None,
+            bin_sha256: None,
+            so_sha256: None,
+            jam_sha256: None,
+            bin_size: None,
+            so_size: None,
+            jam_size: None,
             bin_name,
             package_name: String::new(),
             so_file_name: None,
             jam_file_name: None,
+            base_dir,
             versions_dir,
             current_symlink,
+            previous_version: None,
+            updated_at: None,
+            crash_times: Vec::new(),
+            config,
+            mirror_files_url: None,
+            launcher_update: None,
+            launcher_exe: env::current_exe()?,
         })
     }
 
+    fn data_dir() -> Result<PathBuf> {
+        if let Some(proj_dirs) = ProjectDirs::from("com", "swps", "nockpool-miner") {
+            Ok(proj_dirs.data_dir().to_path_buf())
+        } else {
+            Err(anyhow::anyhow!("Could not determine application data directory"))
+        }
+    }
+
+    fn update_url(&self) -> &str {
+        self.config.update_url.as_deref().unwrap_or(UPDATE_URL)
+    }
+
+    /// Time until the next background update check, randomized by up to
+    /// `UPDATE_JITTER` either way so a fleet of rigs doesn't check in lockstep.
+    fn next_update_delay(&self) -> Duration {
+        let secs = self.config.update_interval_secs.unwrap_or(UPDATE_INTERVAL) as f64;
+        let jitter = rand::thread_rng().gen_range(-UPDATE_JITTER..=UPDATE_JITTER);
+        Duration::from_secs_f64((secs * (1.0 + jitter)).max(1.0))
+    }
+
     fn get_device_info() -> Result<(String, String)> {
         let os_name = match System::name() {
             Some(os) => {
@@ -196,7 +308,7 @@ impl PackageInfo {
         Ok((os_name, arch))
     }
 
-    fn collect_system_info() -> Result<SystemInfo> {
+    fn collect_system_info(data_dir: &Path) -> Result<SystemInfo> {
         let mut sys = System::new_all();
         sys.refresh_all();
 
@@ -250,7 +362,7 @@ impl PackageInfo {
         };
         
         // Storage information
-        let available_disk_space_mb = Self::get_available_disk_space();
+        let available_disk_space_mb = Self::available_disk_space(data_dir).unwrap_or(0) / 1024 / 1024;
         let storage_type = Self::detect_storage_type();
         
         // Network interfaces
@@ -363,7 +475,7 @@ impl PackageInfo {
         
         // Check if nvidia-smi is available and working
         if let Ok(output) = std::process::Command::new("nvidia-smi")
-            .args(&["--query-gpu=name,memory.total,driver_version", "--format=csv,noheader,nounits"])
+            .args(["--query-gpu=name,memory.total,driver_version", "--format=csv,noheader,nounits"])
             .output()
         {
             if output.status.success() {
@@ -418,7 +530,7 @@ impl PackageInfo {
         
         // Check lspci for AMD GPUs
         if let Ok(output) = std::process::Command::new("lspci")
-            .args(&["-nn"])
+            .args(["-nn"])
             .output()
         {
             if output.status.success() {
@@ -460,7 +572,7 @@ impl PackageInfo {
         
         // Check lspci for Intel GPUs
         if let Ok(output) = std::process::Command::new("lspci")
-            .args(&["-nn"])
+            .args(["-nn"])
             .output()
         {
             if output.status.success() {
@@ -552,20 +664,17 @@ impl PackageInfo {
         None
     }
 
-    fn get_available_disk_space() -> u64 {
+    /// Free bytes on the filesystem holding `path`, which doesn't need to exist yet.
+    fn available_disk_space(path: &Path) -> Option<u64> {
+        let path = path.ancestors().find_map(|dir| dir.canonicalize().ok())?;
         let disks = Disks::new_with_refreshed_list();
-        
-        // Get available space for root filesystem
-        for disk in &disks {
-            if disk.mount_point() == std::path::Path::new("/") {
-                return disk.available_space() / 1024 / 1024; // Convert to MB
-            }
-        }
-        
-        // Fallback: sum all available space
-        disks.iter()
+
+        // The disk mounted closest to the path is the one holding it
+        disks
+            .iter()
+            .filter(|disk| path.starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len())
             .map(|disk| disk.available_space())
-            .sum::<u64>() / 1024 / 1024
     }
 
     fn detect_storage_type() -> Option<String> {
@@ -616,94 +725,248 @@ impl PackageInfo {
     }
 
     pub async fn fetch_latest(&mut self) -> Result<()> {
-        let client = Client::new();
+        let client = http::client(&self.config)?;
 
         // First try the enhanced endpoint with system information
-        let system_info = Self::collect_system_info()?;
+        let mut system_info = Self::collect_system_info(&self.base_dir)?;
+        system_info.launcher_config = Some(self.config.request_settings());
 
-        // Sending system information to endpoint for binary selection
-        let enhanced_response = client
-            .post(UPDATE_URL)
-            .header(USER_AGENT, "miner-launcher")
-            .json(&system_info)
-            .send()
-            .await;
-
-        let release_info = match enhanced_response {
-            Ok(res) if res.status().is_success() => {
-                res.json::<ReleaseInfo>().await?
-            }
-            Ok(_res) => {
-                return Err(anyhow::anyhow!(
-                    "This system isn't supported with the launcher. Please build from source."
-                ));
-            }
-            Err(_e) => {
-                return Err(anyhow::anyhow!(
-                    "This system isn't supported with the launcher. Please build from source."
-                ));
+        let update_url = self.update_url().to_string();
+        let result = retry::with_backoff("Update check", FETCH_ATTEMPTS, || {
+            self.request_release(&client, &update_url, &system_info)
+        })
+        .await;
+
+        // A LAN mirror or internal endpoint being down shouldn't keep us from updating
+        let (signed_release, mirror_files_url) = match result {
+            Err(e) if update_url != UPDATE_URL && retry::is_transient(&e) => {
+                warn!("{} is unavailable ({}), falling back to {}", update_url, e, UPDATE_URL);
+                retry::with_backoff("Update check", FETCH_ATTEMPTS, || {
+                    self.request_release(&client, UPDATE_URL, &system_info)
+                })
+                .await?
             }
+            result => result?,
         };
 
+        // Nothing from an unverified response may be acted upon
+        let release_info = signed_release.verify()?;
+        self.apply_release(&release_info)?;
+        self.mirror_files_url = mirror_files_url;
+
+        if let Err(e) = self.save_release_cache(signed_release) {
+            warn!("Failed to cache release information: {}", e);
+        }
+
+        Ok(())
+    }
+
+    fn apply_release(&mut self, release_info: &ReleaseInfo) -> Result<()> {
         // System analysis logging removed for cleaner output
 
         // Use selected_binary if provided, otherwise system not supported
-        if let Some(_selected_binary) = &release_info.selected_binary {
+        if let Some(selected_binary) = &release_info.selected_binary {
 
-            // Extract version from tag_name
-            self.version = release_info.tag_name
-                .split('-')
-                .next()
-                .unwrap_or(&release_info.tag_name)
-                .replace("v", "");
+            self.version = release_info.version();
 
             // Check for .so file URL directly from response
             if let Some(ref selected_so_url) = release_info.selected_so_file {
                 self.so_download_url = Some(selected_so_url.clone());
+                self.so_sha256 = Self::find_asset_digest(&release_info.assets, selected_so_url);
+                self.so_size = Self::find_asset_size(&release_info.assets, selected_so_url);
                 // Extract filename from URL
-                if let Some(filename) = selected_so_url.split('/').last() {
+                if let Some(filename) = selected_so_url.split('/').next_back() {
                     self.so_file_name = Some(filename.to_string());
                 }
+                info!("Using server-selected .so file {}", selected_so_url);
+            } else if let Some(asset) = release_info
+                .assets
+                .iter()
+                .find(|asset| self.is_compatible_so_asset(&asset.name))
+            {
+                info!("Server selected no .so file, using {} from the release assets", asset.name);
+                self.so_download_url = Some(asset.browser_download_url.clone());
+                self.so_sha256 = asset.digest.clone();
+                self.so_size = asset.size;
+                self.so_file_name = Some(asset.name.clone());
+            } else {
+                // This release has no .so file, don't carry over the last one's
+                self.so_download_url = None;
+                self.so_sha256 = None;
+                self.so_size = None;
+                self.so_file_name = None;
             }
 
             // Check for .jam file URL directly from response
             if let Some(ref selected_jam_url) = release_info.selected_jam_file {
                 self.jam_download_url = Some(selected_jam_url.clone());
+                self.jam_sha256 = Self::find_asset_digest(&release_info.assets, selected_jam_url);
+                self.jam_size = Self::find_asset_size(&release_info.assets, selected_jam_url);
                 // Extract filename from URL
-                if let Some(filename) = selected_jam_url.split('/').last() {
+                if let Some(filename) = selected_jam_url.split('/').next_back() {
                     self.jam_file_name = Some(filename.to_string());
                 }
+                info!("Using server-selected .jam file {}", selected_jam_url);
+            } else if let Some(asset) = release_info
+                .assets
+                .iter()
+                .find(|asset| self.is_compatible_jam_asset(&asset.name))
+            {
+                info!("Server selected no .jam file, using {} from the release assets", asset.name);
+                self.jam_download_url = Some(asset.browser_download_url.clone());
+                self.jam_sha256 = asset.digest.clone();
+                self.jam_size = asset.size;
+                self.jam_file_name = Some(asset.name.clone());
+            } else {
+                self.jam_download_url = None;
+                self.jam_sha256 = None;
+                self.jam_size = None;
+                self.jam_file_name = None;
             }
 
-            // Find the appropriate binary asset
-            let mut found_binary = false;
-
-            for asset in &release_info.assets {
-                // Check if this asset matches our binary
-                if self.is_compatible_asset(&asset.name, _selected_binary) {
-                    self.download_url = asset.browser_download_url.clone();
-                    self.package_name = asset.name.clone();
-                    found_binary = true;
-                    break;
+            // Prefer the exact asset the server selected for this machine
+            let asset = match Self::find_asset(&release_info.assets, selected_binary) {
+                Some(asset) => {
+                    info!("Using server-selected binary {}", asset.name);
+                    asset
                 }
-            }
+                None => {
+                    warn!(
+                        "Selected binary {} is not among the release assets, matching by OS and architecture instead",
+                        selected_binary
+                    );
+                    let asset = release_info
+                        .assets
+                        .iter()
+                        .find(|asset| self.is_compatible_asset(&asset.name))
+                        .ok_or_else(|| anyhow::anyhow!("No compatible binary asset found in release"))?;
+                    warn!("Falling back to {} for {}/{}", asset.name, self.os_name, self.arch);
+                    asset
+                }
+            };
 
-            if found_binary {
-                return Ok(());
-            } else {
-                return Err(anyhow::anyhow!("No compatible binary asset found in release"));
-            }
+            self.download_url = asset.browser_download_url.clone();
+            self.package_name = asset.name.clone();
+            self.bin_sha256 = asset.digest.clone();
+            self.bin_size = asset.size;
+            self.launcher_update = release_info.launcher.clone();
+            return Ok(());
         }
 
-        Err(anyhow::anyhow!(
-            "This system isn't supported with the launcher. Please build from source."
-        ))
+        Err(UpdateError::Unsupported.into())
     }
 
-    fn is_compatible_asset(&self, asset_name: &str, _selected_binary: &str) -> bool {
-        // Simple compatibility check - in a real implementation, this would be more sophisticated
+    fn release_cache_path(&self) -> PathBuf {
+        self.base_dir.join("release_cache.json")
+    }
+
+    fn save_release_cache(&self, release: SignedRelease) -> Result<()> {
+        let cached = CachedRelease {
+            fetched_at: Utc::now(),
+            release,
+        };
+
+        fs::create_dir_all(&self.base_dir)?;
+        let path = self.release_cache_path();
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(&cached)?)?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+
+    /// Restores what the last successful update check said about the installed
+    /// version, so its .so/.jam file names are known without network access.
+    pub fn load_cached_release(&mut self) -> Result<()> {
+        let contents = match fs::read_to_string(self.release_cache_path()) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let cached: CachedRelease = serde_json::from_str(&contents)?;
+        let release_info = cached.release.verify()?;
+
+        // The cache only describes the installed version if that's what it offered
+        let mut candidate = self.clone();
+        candidate.apply_release(&release_info)?;
+        if self.get_local_version().as_deref() != Some(candidate.version.as_str()) {
+            return Ok(());
+        }
+
+        info!(
+            "Using cached release information for {} from {}",
+            candidate.version,
+            cached.fetched_at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+        );
+        *self = candidate;
+        Ok(())
+    }
+
+    /// Returns the signed release, plus where to fetch its files if it came from a LAN mirror.
+    async fn request_release(
+        &self,
+        client: &Client,
+        update_url: &str,
+        system_info: &SystemInfo,
+    ) -> Result<(SignedRelease, Option<String>)> {
+        // Sending system information to endpoint for binary selection
+        let res = client
+            .post(update_url)
+            .timeout(http::read_timeout(&self.config))
+            .json(system_info)
+            .send()
+            .await
+            .map_err(UpdateError::Network)?;
+
+        let status = res.status();
+        if UpdateError::is_transient_status(status) {
+            return Err(UpdateError::Server(status).into());
+        }
+        if !status.is_success() {
+            return Err(UpdateError::Unsupported.into());
+        }
+
+        let signature = res
+            .headers()
+            .get(signing::SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let mirror_files_url = res
+            .headers()
+            .get(mirror::MIRROR_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|path| reqwest::Url::parse(update_url).ok()?.join(path).ok())
+            .map(|url| url.to_string());
+        let body = res.text().await.map_err(UpdateError::Network)?;
+
+        Ok((SignedRelease { body, signature }, mirror_files_url))
+    }
+
+    /// The asset `selected` refers to, given either its name or its download URL.
+    fn find_asset<'a>(assets: &'a [Asset], selected: &str) -> Option<&'a Asset> {
+        let file_name = selected.split('/').next_back().unwrap_or(selected);
+        assets
+            .iter()
+            .find(|asset| asset.browser_download_url == selected || asset.name == file_name)
+    }
+
+    fn find_asset_digest(assets: &[Asset], url: &str) -> Option<String> {
+        Self::find_asset(assets, url).and_then(|asset| asset.digest.clone())
+    }
+
+    fn find_asset_size(assets: &[Asset], url: &str) -> Option<u64> {
+        Self::find_asset(assets, url).and_then(|asset| asset.size)
+    }
+
+    /// Loose OS/architecture match on the asset name, only used when the
+    /// server's selection isn't among the assets.
+    fn is_compatible_asset(&self, asset_name: &str) -> bool {
         let asset_lower = asset_name.to_lowercase();
 
+        // The .so and .jam files carry the same OS/arch tags as the binary
+        if asset_lower.ends_with(".so") || asset_lower.ends_with(".jam") {
+            return false;
+        }
+
         // Check for basic OS and architecture compatibility
         let os_match = asset_lower.contains(&self.os_name);
         let arch_match = asset_lower.contains(&self.arch);
@@ -711,7 +974,7 @@ impl PackageInfo {
         os_match && arch_match
     }
 
-    fn is_compatible_so_asset(&self, asset_name: &str, _selected_so: &str) -> bool {
+    fn is_compatible_so_asset(&self, asset_name: &str) -> bool {
         let asset_lower = asset_name.to_lowercase();
 
         // Check if it's a .so file and matches our system
@@ -724,7 +987,7 @@ impl PackageInfo {
         false
     }
 
-    fn is_compatible_jam_asset(&self, asset_name: &str, _selected_jam: &str) -> bool {
+    fn is_compatible_jam_asset(&self, asset_name: &str) -> bool {
         let asset_lower = asset_name.to_lowercase();
 
         // Check if it's a .jam file and matches our system
@@ -740,8 +1003,8 @@ impl PackageInfo {
     pub fn get_local_version(&self) -> Option<String> {
         if self.current_symlink.exists() {
             let real_path = fs::read_link(&self.current_symlink).ok()?;
-            let version = real_path.file_name()?.to_str()?.to_string();
-            Some(version)
+            let version = dir_version(real_path.file_name()?.to_str()?);
+            Some(version.to_string())
         } else {
             None
         }
@@ -749,7 +1012,7 @@ impl PackageInfo {
 
     fn check_addon_files_exist(&self) -> (bool, bool) {
         if let Some(version) = &self.get_local_version() {
-            let version_dir = self.versions_dir.join(version);
+            let version_dir = self.version_dir(version);
 
             let so_exists = if let Some(so_name) = &self.so_file_name {
                 version_dir.join(so_name).exists()
@@ -769,10 +1032,79 @@ impl PackageInfo {
         }
     }
 
+    /// The .so/.jam files the installed version needs but doesn't have, going by
+    /// the last release information, possibly restored from the cache.
+    fn missing_addon_files(&self) -> Vec<&str> {
+        let (so_exists, jam_exists) = self.check_addon_files_exist();
+        let mut missing = Vec::new();
+        if let (false, Some(so_name)) = (so_exists, &self.so_file_name) {
+            missing.push(so_name.as_str());
+        }
+        if let (false, Some(jam_name)) = (jam_exists, &self.jam_file_name) {
+            missing.push(jam_name.as_str());
+        }
+        missing
+    }
+
+    /// Decides whether the version from the last `fetch_latest` may replace the
+    /// local one, honoring a pinned version and downgrade protection.
+    fn accepts_remote_version(&self, local_version: Option<&str>) -> bool {
+        if let Some(pin) = &self.config.pin_version {
+            if versions_equal(pin, &self.version) {
+                return true;
+            }
+            if local_version.is_some_and(|local| versions_equal(pin, local)) {
+                info!("Pinned to version {}, ignoring {} from the server", pin, self.version);
+            } else {
+                warn!("Pinned to version {}, but the server offered {}", pin, self.version);
+            }
+            return false;
+        }
+
+        let Some(local_version) = local_version else {
+            return true;
+        };
+
+        match (parse_version(local_version), parse_version(&self.version)) {
+            (Some(local), Some(remote))
+                if remote < local && !self.config.allow_downgrade && !self.config.channel_downgrade_pending =>
+            {
+                warn!(
+                    "Server offered {} which is older than the installed {}, not downgrading (use --allow-downgrade to allow it)",
+                    self.version, local_version
+                );
+                false
+            }
+            (None, _) | (_, None) if local_version != self.version => {
+                warn!(
+                    "Can't compare versions {} and {}, treating {} as an update",
+                    local_version, self.version, self.version
+                );
+                true
+            }
+            _ => true,
+        }
+    }
+
     pub async fn ensure_latest_version(&mut self) -> Result<()> {
         let local_version = self.get_local_version();
         self.fetch_latest().await?;
 
+        if local_version.is_some() && self.is_blacklisted(&self.version) {
+            warn!("Version {} previously failed on this machine, skipping it", self.version);
+            return Ok(());
+        }
+
+        if !self.accepts_remote_version(local_version.as_deref()) {
+            if local_version.is_none() {
+                return Err(anyhow::anyhow!(
+                    "Version {} offered by the server can't be installed and nothing is installed yet",
+                    self.version
+                ));
+            }
+            return Ok(());
+        }
+
         let version_mismatch = match local_version {
             Some(lv) => lv != self.version,
             None => true,
@@ -805,36 +1137,448 @@ impl PackageInfo {
     }
 
     async fn download_and_install(&self) -> Result<()> {
-        let version_dir = self.versions_dir.join(&self.version);
-        fs::create_dir_all(&version_dir)?;
+        // Download and verify everything before touching the version directory,
+        // so a bad download never replaces a working install. Files are kept in
+        // downloads/<version> until installed so an interrupted download resumes.
+        let download_dir = self.base_dir.join("downloads").join(&self.version);
+        self.cleanup_downloads()?;
+        fs::create_dir_all(&download_dir)?;
+        self.ensure_disk_space(&download_dir)?;
+
+        let client = http::client(&self.config)?;
+
+        // Fetch all files at once; if any of them fails the others are dropped,
+        // leaving their partial downloads to resume next time
+        let so_wanted = self.so_download_url.is_some() && self.so_file_name.is_some();
+        let jam_wanted = self.jam_download_url.is_some() && self.jam_file_name.is_some();
+        let total = [
+            Some(self.bin_size),
+            so_wanted.then_some(self.so_size),
+            jam_wanted.then_some(self.jam_size),
+        ]
+        .into_iter()
+        .flatten()
+        .sum::<Option<u64>>();
+        let progress = Arc::new(CombinedProgress::new(total));
+
+        let bin_file = download_dir.join(&self.package_name);
+        let (_, so_file, jam_file) = tokio::try_join!(
+            self.download_verified(&client, &self.download_url, &bin_file, self.bin_sha256.as_deref(), &progress),
+            self.download_optional(
+                &client,
+                self.so_download_url.as_deref(),
+                self.so_file_name.as_deref(),
+                &download_dir,
+                self.so_sha256.as_deref(),
+                &progress
+            ),
+            self.download_optional(
+                &client,
+                self.jam_download_url.as_deref(),
+                self.jam_file_name.as_deref(),
+                &download_dir,
+                self.jam_sha256.as_deref(),
+                &progress
+            ),
+        )?;
+        progress.finish();
+
+        // Assemble the install in a staging directory and only move it into
+        // versions/<version> once it is complete
+        let staging_dir = self.create_staging_dir(&self.version)?;
+
+        let mut result = self
+            .write_install_files(&staging_dir, &bin_file, so_file.as_deref(), jam_file.as_deref())
+            .and_then(|_| self.validate_install(&staging_dir));
+
+        if result.is_ok() {
+            result = self.health_check(&staging_dir).await;
+
+            // Don't download the same broken build again on every check. Only a
+            // new version that ran and failed counts; a timeout or spawn error
+            // may just be a busy rig, and the running version must stay repairable.
+            let exited_with_error = result
+                .as_ref()
+                .err()
+                .and_then(|e| e.downcast_ref::<UpdateError>())
+                .is_some_and(|e| matches!(e, UpdateError::HealthCheckFailed(_)));
+            if exited_with_error && self.get_local_version().as_deref() != Some(self.version.as_str()) {
+                if let Err(e) = self.blacklist_version(&self.version) {
+                    warn!("Failed to blacklist version {}: {}", self.version, e);
+                }
+            }
+        }
 
-        // Download main binary
-        let response = reqwest::get(&self.download_url).await?;
-        let bytes = response.bytes().await?;
-        let bin_path = version_dir.join(&self.bin_name);
+        let result = result.and_then(|_| self.commit_staged_install(&staging_dir));
 
-        if self.os_name == "macos" {
-            let mut archive = ZipArchive::new(Cursor::new(bytes))?;
-            archive.extract(&version_dir)?;
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&staging_dir);
         } else {
-            let mut file = fs::File::create(&bin_path)?;
-            file.write_all(&bytes)?;
+            let _ = fs::remove_dir_all(&download_dir);
+        }
+
+        result
+    }
+
+    /// Replaces the launcher executable if the last `fetch_latest` advertised a
+    /// newer build. Returns true if the launcher needs to be re-executed.
+    pub async fn update_launcher(&self) -> Result<bool> {
+        if self.config.disable_self_update {
+            return Ok(false);
+        }
+        let Some(release) = self.launcher_update.as_ref().filter(|release| release.is_newer()) else {
+            return Ok(false);
+        };
+
+        info!(
+            "Launcher {} is available (running {}), updating...",
+            release.version,
+            env!("CARGO_PKG_VERSION")
+        );
+        let client = http::client(&self.config)?;
+        self_update::install(
+            &client,
+            release,
+            &self.launcher_exe,
+            &self.base_dir.join("launcher"),
+            http::read_timeout(&self.config),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Bytes this update still needs: what is left to download, plus room for
+    /// the staged copy of every file and a safety margin.
+    fn required_disk_space(&self, download_dir: &Path) -> u64 {
+        let files = [
+            (Some(&self.package_name), self.bin_size),
+            (self.so_file_name.as_ref(), self.so_size),
+            (self.jam_file_name.as_ref(), self.jam_size),
+        ];
+
+        let mut needed = DISK_SPACE_MARGIN;
+        for (name, size) in files {
+            let (Some(name), Some(size)) = (name, size) else {
+                continue;
+            };
+            let dest = download_dir.join(name);
+            let downloaded = fs::metadata(&dest)
+                .or_else(|_| fs::metadata(download::part_path(&dest)))
+                .map(|meta| meta.len())
+                .unwrap_or(0);
+            needed += size.saturating_sub(downloaded) + size;
+        }
+        needed
+    }
+
+    /// Checks the filesystem holding `versions_dir` has room for this update,
+    /// pruning old versions first if that is what it takes.
+    fn ensure_disk_space(&self, download_dir: &Path) -> Result<()> {
+        let needed = self.required_disk_space(download_dir);
+        let Some(available) = Self::available_disk_space(&self.versions_dir) else {
+            warn!("Couldn't determine free disk space for {}", self.versions_dir.display());
+            return Ok(());
+        };
+        if available >= needed {
+            return Ok(());
+        }
+
+        warn!(
+            "Only {} MB free for version {}, which needs {} MB; removing old versions",
+            available / 1024 / 1024,
+            self.version,
+            needed / 1024 / 1024
+        );
+        let removed = self.prune_versions(0)?;
+        if !removed.is_empty() {
+            info!("Removed old versions: {}", removed.join(", "));
+        }
+
+        let available = Self::available_disk_space(&self.versions_dir).unwrap_or(0);
+        if available < needed {
+            return Err(UpdateError::InsufficientSpace {
+                needed_mb: needed / 1024 / 1024,
+                available_mb: available / 1024 / 1024,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Removes partial downloads for versions other than the one being installed.
+    fn cleanup_downloads(&self) -> Result<()> {
+        let Ok(entries) = fs::read_dir(self.base_dir.join("downloads")) else {
+            return Ok(());
+        };
+
+        for entry in entries.flatten() {
+            if entry.file_name().to_str() != Some(self.version.as_str()) {
+                fs::remove_dir_all(entry.path())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn create_staging_dir(&self, label: &str) -> Result<PathBuf> {
+        let staging_dir = self.versions_dir.join(format!("{}{}-{}", STAGING_PREFIX, std::process::id(), label));
+        if staging_dir.exists() {
+            fs::remove_dir_all(&staging_dir)?;
+        }
+        fs::create_dir_all(&staging_dir)?;
+        Ok(staging_dir)
+    }
+
+    /// Installs a version from an offline bundle and makes it current, the same
+    /// way a downloaded update is installed.
+    pub fn install_from_bundle(&mut self, bundle_path: &Path, allow_unsigned: bool) -> Result<()> {
+        let staging_dir = self.create_staging_dir("bundle")?;
+
+        let result = self.stage_bundle(bundle_path, &staging_dir, allow_unsigned);
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&staging_dir);
+        }
+        let manifest = result?;
+
+        self.update_symlink()?;
+
+        if let Some(release) = manifest.release {
+            // Lets the installed version's file names be known offline, as after a network update
+            if let Err(e) = self.save_release_cache(release) {
+                warn!("Failed to cache release information: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn stage_bundle(
+        &mut self,
+        bundle_path: &Path,
+        staging_dir: &Path,
+        allow_unsigned: bool,
+    ) -> Result<bundle::BundleManifest> {
+        let manifest = bundle::unpack(bundle_path, staging_dir)?;
+
+        if manifest.bin_name != self.bin_name {
+            return Err(anyhow::anyhow!(
+                "Bundle contains {}, expected {}",
+                manifest.bin_name,
+                self.bin_name
+            ));
+        }
+
+        match &manifest.release {
+            Some(release) => self.verify_bundle_release(&manifest, release)?,
+            None if allow_unsigned => warn!("Bundle has no signed release information, installing it unverified"),
+            None => {
+                return Err(anyhow::anyhow!(
+                    "Bundle has no signed release information, refusing to install it (use --allow-unsigned to install it anyway)"
+                ))
+            }
+        }
+
+        if let Some(asset) = &manifest.bin_asset {
+            self.extract_bundled_asset(staging_dir, asset)?;
         }
 
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(&bin_path, fs::Permissions::from_mode(0o755))?;
+            fs::set_permissions(staging_dir.join(&self.bin_name), fs::Permissions::from_mode(0o755))?;
+            if let Some(so_file) = &manifest.so_file {
+                fs::set_permissions(staging_dir.join(so_file), fs::Permissions::from_mode(0o755))?;
+            }
         }
 
-        // Download .so file if available
-        if let (Some(so_url), Some(so_name)) = (&self.so_download_url, &self.so_file_name) {
-            let so_response = reqwest::get(so_url).await?;
-            let so_bytes = so_response.bytes().await?;
-            let so_path = version_dir.join(so_name);
+        self.version = manifest.version.clone();
+        self.so_file_name = manifest.so_file.clone();
+        self.jam_file_name = manifest.jam_file.clone();
+
+        self.validate_install(staging_dir)?;
+        self.commit_staged_install(staging_dir)?;
+        info!("Installed version {} from {}", self.version, bundle_path.display());
+
+        Ok(manifest)
+    }
 
-            let mut so_file = fs::File::create(&so_path)?;
-            so_file.write_all(&so_bytes)?;
+    /// Extracts the binary from a bundled release archive. The archive is unpacked
+    /// on its own first so nothing else in it can overwrite the verified files.
+    fn extract_bundled_asset(&self, staging_dir: &Path, asset: &str) -> Result<()> {
+        let asset_path = staging_dir.join(asset);
+        let kind = ArchiveKind::detect(&asset_path, asset)?;
+        if kind == ArchiveKind::Raw {
+            return Err(anyhow::anyhow!("Bundled {} is not an archive", asset));
+        }
+
+        let extract_dir = staging_dir.join(".extract");
+        fs::create_dir_all(&extract_dir)?;
+        let result = archive::extract(&asset_path, kind, &extract_dir).and_then(|_| {
+            let extracted = extract_dir.join(&self.bin_name);
+            if !extracted.is_file() {
+                return Err(anyhow::anyhow!("{} does not contain {} at its top level", asset, self.bin_name));
+            }
+            Ok(fs::rename(extracted, staging_dir.join(&self.bin_name))?)
+        });
+        let _ = fs::remove_dir_all(&extract_dir);
+        result
+    }
+
+    /// Checks that a bundle's files are the ones its signed release published,
+    /// since the manifest checksums alone say nothing about where they came from.
+    fn verify_bundle_release(&self, manifest: &bundle::BundleManifest, release: &SignedRelease) -> Result<()> {
+        let release_info = release.verify()?;
+        if release_info.version() != manifest.version {
+            return Err(anyhow::anyhow!(
+                "Bundle claims version {} but its signed release is {}",
+                manifest.version,
+                release_info.version()
+            ));
+        }
+
+        let mut signed = self.clone();
+        signed.apply_release(&release_info)?;
+
+        if let Some(asset) = &manifest.bin_asset {
+            if *asset != signed.package_name {
+                return Err(anyhow::anyhow!(
+                    "Bundle extracts its binary from {}, but its signed release publishes {}",
+                    asset,
+                    signed.package_name
+                ));
+            }
+        }
+
+        for (name, digest) in &manifest.files {
+            // The binary is installed under `bin_name` rather than its asset name
+            let signed_digest = if *name == self.bin_name {
+                signed.bin_sha256.clone()
+            } else {
+                Self::find_asset_digest(&release_info.assets, name)
+            };
+            let signed_digest = signed_digest.ok_or_else(|| {
+                anyhow::anyhow!("{} in the bundle has no checksum in its signed release", name)
+            })?;
+            if !digests_equal(digest, &signed_digest) {
+                return Err(anyhow::anyhow!(
+                    "{} in the bundle does not match the checksum in its signed release",
+                    name
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Packs the active version into a bundle that `install --from` accepts.
+    pub fn export_bundle(&self, output: Option<PathBuf>) -> Result<PathBuf> {
+        let version = self
+            .get_local_version()
+            .ok_or_else(|| anyhow::anyhow!("No current version installed, nothing to export"))?;
+        let version_dir = self.version_dir(&version);
+
+        let mut files = bundle::checksums(&version_dir)?;
+        let find_file = |known: &Option<String>, extension: &str| {
+            known.clone().or_else(|| {
+                files
+                    .keys()
+                    .find(|name| name.ends_with(extension))
+                    .cloned()
+            })
+        };
+        let so_file = find_file(&self.so_file_name, ".so");
+        let jam_file = find_file(&self.jam_file_name, ".jam");
+
+        // Include the signed release this version came from, if the cache still describes it
+        let mut release = fs::read_to_string(self.release_cache_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str::<CachedRelease>(&contents).ok())
+            .map(|cached| cached.release)
+            .filter(|release| release.verify().is_ok_and(|info| info.version() == version));
+
+        // The signed release only vouches for the published asset, so a binary that
+        // was extracted from an archive is bundled as that archive
+        let mut bin_asset = None;
+        if let Some(info) = release.as_ref().and_then(|release| release.verify().ok()) {
+            let mut signed = self.clone();
+            signed.apply_release(&info)?;
+            let raw = match (files.get(&self.bin_name), &signed.bin_sha256) {
+                (Some(digest), Some(signed_digest)) => digests_equal(digest, signed_digest),
+                _ => false,
+            };
+            if !raw {
+                if files.contains_key(&signed.package_name) {
+                    bin_asset = Some(signed.package_name);
+                } else {
+                    warn!(
+                        "Version {} was installed without keeping its release archive, so the bundle can only be installed with --allow-unsigned",
+                        version
+                    );
+                    release = None;
+                }
+            }
+        }
+
+        // Only the files the version is made of, not whatever else was extracted
+        let bin_file = bin_asset.clone().unwrap_or_else(|| self.bin_name.clone());
+        files.retain(|name, _| [Some(&bin_file), so_file.as_ref(), jam_file.as_ref()].contains(&Some(name)));
+
+        let manifest = bundle::BundleManifest {
+            version: version.clone(),
+            bin_name: self.bin_name.clone(),
+            so_file,
+            jam_file,
+            bin_asset,
+            files,
+            release,
+        };
+
+        let output = output.unwrap_or_else(|| PathBuf::from(format!("nockpool-miner-{}.zip", version)));
+        bundle::write_zip(&version_dir, &manifest, &output)?;
+        Ok(output)
+    }
+
+    fn write_install_files(
+        &self,
+        dir: &Path,
+        bin_file: &Path,
+        so_file: Option<&Path>,
+        jam_file: Option<&Path>,
+    ) -> Result<()> {
+        // Install main binary
+        let bin_path = dir.join(&self.bin_name);
+
+        match ArchiveKind::detect(bin_file, &self.package_name)? {
+            ArchiveKind::Raw => {
+                fs::copy(bin_file, &bin_path)?;
+            }
+            kind => {
+                archive::extract(bin_file, kind, dir)?;
+                if !bin_path.is_file() {
+                    return Err(anyhow::anyhow!(
+                        "{} does not contain {} at its top level",
+                        self.package_name,
+                        self.bin_name
+                    ));
+                }
+                // Keep the published archive too, since it is what the signed release
+                // vouches for when this version is exported or served to the LAN
+                if self.package_name != self.bin_name {
+                    fs::copy(bin_file, dir.join(&self.package_name))?;
+                }
+            }
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&bin_path, fs::Permissions::from_mode(0o755))?;
+        }
+
+        // Install .so file if available
+        if let (Some(so_file), Some(so_name)) = (so_file, &self.so_file_name) {
+            let so_path = dir.join(so_name);
+            fs::copy(so_file, &so_path)?;
 
             #[cfg(unix)]
             {
@@ -843,14 +1587,10 @@ impl PackageInfo {
             }
         }
 
-        // Download .jam file if available
-        if let (Some(jam_url), Some(jam_name)) = (&self.jam_download_url, &self.jam_file_name) {
-            let jam_response = reqwest::get(jam_url).await?;
-            let jam_bytes = jam_response.bytes().await?;
-            let jam_path = version_dir.join(jam_name);
-
-            let mut jam_file = fs::File::create(&jam_path)?;
-            jam_file.write_all(&jam_bytes)?;
+        // Install .jam file if available
+        if let (Some(jam_file), Some(jam_name)) = (jam_file, &self.jam_file_name) {
+            let jam_path = dir.join(jam_name);
+            fs::copy(jam_file, &jam_path)?;
 
             #[cfg(unix)]
             {
@@ -859,22 +1599,427 @@ impl PackageInfo {
             }
         }
 
+        // Make sure everything is on disk before the directory is renamed into place
+        for entry in fs::read_dir(dir)?.flatten() {
+            if entry.file_type()?.is_file() {
+                fs::File::open(entry.path())?.sync_all()?;
+            }
+        }
+
         Ok(())
     }
 
-    fn update_symlink(&self) -> Result<()> {
-        let version_dir = self.versions_dir.join(&self.version);
+    fn validate_install(&self, dir: &Path) -> Result<()> {
+        if !dir.join(&self.bin_name).is_file() {
+            return Err(anyhow::anyhow!("Installed package is missing {}", self.bin_name));
+        }
+
+        for name in [&self.so_file_name, &self.jam_file_name].into_iter().flatten() {
+            if !dir.join(name).is_file() {
+                return Err(anyhow::anyhow!("Installed package is missing {}", name));
+            }
+        }
 
-        if self.current_symlink.exists() {
-            fs::remove_file(&self.current_symlink)?;
+        Ok(())
+    }
+
+    /// Runs the staged binary with `--version` before it can replace the running
+    /// version, so a build that doesn't start on this machine is never switched to.
+    async fn health_check(&self, dir: &Path) -> Result<()> {
+        let mut command = Command::new(dir.join(&self.bin_name));
+        command
+            .arg("--version")
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let output = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, command.output()).await {
+            Ok(output) => output.map_err(|e| anyhow::anyhow!("Failed to run version {}: {}", self.version, e))?,
+            Err(_) => {
+                return Err(anyhow::anyhow!(
+                    "Version {} did not answer --version within {} seconds",
+                    self.version,
+                    HEALTH_CHECK_TIMEOUT.as_secs()
+                ))
+            }
+        };
+
+        if !output.status.success() {
+            return Err(UpdateError::HealthCheckFailed(format!(
+                "version {} exited with {}: {}",
+                self.version,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+            .into());
+        }
+
+        info!(
+            "Version {} passed its health check: {}",
+            self.version,
+            String::from_utf8_lossy(&output.stdout).trim()
+        );
+        Ok(())
+    }
+
+    fn commit_staged_install(&self, staging_dir: &Path) -> Result<()> {
+        let existing_dir = self.version_dir(&self.version);
+        if !existing_dir.exists() {
+            fs::rename(staging_dir, &existing_dir)?;
+            return Ok(());
+        }
+
+        // Re-installing an existing version (e.g. missing addon files). The old copy
+        // may be the one `current` points at, so it is never moved: the new copy gets
+        // a fresh name, `current` switches to it, and only then is the old one removed
+        let fresh_dir = self.versions_dir.join(format!(
+            "{}{}{}",
+            self.version,
+            REINSTALL_SEPARATOR,
+            Utc::now().timestamp_millis()
+        ));
+        fs::rename(staging_dir, &fresh_dir)?;
+
+        if self.current_dir().as_ref() == Some(&existing_dir) {
+            if let Err(e) = self.point_current_at(&fresh_dir) {
+                let _ = fs::remove_dir_all(&fresh_dir);
+                return Err(e);
+            }
+        }
+        if let Err(e) = fs::remove_dir_all(&existing_dir) {
+            warn!("Failed to remove the replaced copy of {}: {}", self.version, e);
         }
 
+        Ok(())
+    }
+
+    /// Removes staging directories left behind by an interrupted install.
+    pub fn cleanup_staging(&self) -> Result<()> {
+        let entries = match fs::read_dir(&self.versions_dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+
+            if name.starts_with(STAGING_PREFIX) {
+                info!("Removing leftover staging directory {}", name);
+                fs::remove_dir_all(&path)?;
+            } else if let Some(rest) = name.strip_prefix(REPLACED_PREFIX) {
+                // Older launchers moved the old copy aside during a re-install, and a
+                // crash there left nothing in its place, so put it back rather than lose it
+                let version = rest.split_once('-').map(|(_, version)| version).unwrap_or(rest);
+                let version_dir = self.version_dir(version);
+                if version_dir.exists() {
+                    fs::remove_dir_all(&path)?;
+                } else {
+                    info!("Restoring version {} from an interrupted re-install", version);
+                    fs::rename(&path, &version_dir)?;
+                }
+            }
+        }
+
+        // A crash during a re-install can leave two copies of a version. Keep the
+        // one `current` uses, or else the newest
+        for (version, path) in self.installed_dirs() {
+            if path != self.version_dir(&version) {
+                info!("Removing a stale copy of version {}", version);
+                fs::remove_dir_all(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Downloads an addon file into `dir` if the release has one.
+    async fn download_optional(
+        &self,
+        client: &Client,
+        url: Option<&str>,
+        name: Option<&str>,
+        dir: &Path,
+        expected_sha256: Option<&str>,
+        progress: &Arc<CombinedProgress>,
+    ) -> Result<Option<PathBuf>> {
+        let (Some(url), Some(name)) = (url, name) else {
+            return Ok(None);
+        };
+        let dest = dir.join(name);
+        self.download_verified(client, url, &dest, expected_sha256, progress).await?;
+        Ok(Some(dest))
+    }
+
+    async fn download_verified(
+        &self,
+        client: &Client,
+        url: &str,
+        dest: &Path,
+        expected_sha256: Option<&str>,
+        progress: &Arc<CombinedProgress>,
+    ) -> Result<()> {
+        let name = dest.file_name().and_then(|name| name.to_str()).unwrap_or(url);
+        let read_timeout = http::read_timeout(&self.config);
+
+        let Some(expected_sha256) = expected_sha256 else {
+            if !self.config.allow_missing_checksums {
+                return Err(anyhow::anyhow!("No checksum published for {}, refusing to install it", name));
+            }
+            // Only upstream, since nothing vouches for what a mirror would serve
+            warn!("No checksum published for {}, installing it unverified", name);
+            return download::download_to_file(client, url, dest, download::DOWNLOAD_ATTEMPTS, read_timeout, Some(progress)).await;
+        };
+
+        // Prefer the LAN mirror the release came from; the signed checksum keeps it honest
+        if let Some(mirror_files_url) = &self.mirror_files_url {
+            let mirror_url = format!("{}{}/{}", mirror_files_url, self.version, name);
+            let result = download::download_to_file(client, &mirror_url, dest, MIRROR_DOWNLOAD_ATTEMPTS, read_timeout, Some(progress))
+                .await
+                .and_then(|_| download::verify_sha256(dest, name, expected_sha256));
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    info!("{} not available from the mirror ({}), downloading from upstream", name, e);
+                    download::discard(dest);
+                }
+            }
+        }
+
+        download::download_to_file(client, url, dest, download::DOWNLOAD_ATTEMPTS, read_timeout, Some(progress)).await?;
+        download::verify_sha256(dest, name, expected_sha256)
+    }
+
+    fn update_symlink(&mut self) -> Result<()> {
+        let version_dir = self.version_dir(&self.version);
+        let previous_version = self.get_local_version();
+        self.point_current_at(&version_dir)?;
+
+        // Remember what we switched away from so a bad release can be rolled back
+        if previous_version.as_deref() != Some(self.version.as_str()) {
+            self.previous_version = previous_version;
+            self.updated_at = Some(Instant::now());
+            self.crash_times.clear();
+        }
+
+        match self.prune_versions(KEEP_VERSIONS) {
+            Ok(removed) if !removed.is_empty() => info!("Removed old versions: {}", removed.join(", ")),
+            Ok(_) => {}
+            Err(e) => warn!("Failed to remove old versions: {}", e),
+        }
+
+        Ok(())
+    }
+
+    /// Deletes installed versions beyond the `keep` most recent ones. The active
+    /// version and the rollback target are always kept.
+    pub fn prune_versions(&self, keep: usize) -> Result<Vec<String>> {
+        let active_version = self.get_local_version();
+        let mut removed = Vec::new();
+
+        for version in self.installed_versions().into_iter().skip(keep) {
+            if active_version.as_ref() == Some(&version) || self.previous_version.as_ref() == Some(&version) {
+                continue;
+            }
+            for (_, path) in self.installed_dirs().into_iter().filter(|(v, _)| *v == version) {
+                fs::remove_dir_all(path)?;
+            }
+            removed.push(version);
+        }
+
+        Ok(removed)
+    }
+
+    /// Records an unexpected miner exit. If a freshly installed version keeps
+    /// crashing, `current` is pointed back at the previous version and the failed
+    /// one is blacklisted. Returns true if a rollback happened.
+    pub fn record_crash(&mut self) -> Result<bool> {
+        let Some(updated_at) = self.updated_at else {
+            return Ok(false);
+        };
+        if updated_at.elapsed() > ROLLBACK_WINDOW {
+            // The new version survived long enough, stop watching it
+            self.updated_at = None;
+            self.crash_times.clear();
+            return Ok(false);
+        }
+
+        self.crash_times.push(Instant::now());
+        if self.crash_times.len() < ROLLBACK_CRASH_THRESHOLD {
+            return Ok(false);
+        }
+
+        let (Some(previous), Some(failed)) = (self.previous_version.clone(), self.get_local_version()) else {
+            return Ok(false);
+        };
+        let previous_dir = self.version_dir(&previous);
+        if !previous_dir.join(&self.bin_name).is_file() {
+            warn!("Cannot roll back to {}, it is no longer installed", previous);
+            return Ok(false);
+        }
+
+        warn!(
+            "Version {} crashed {} times within {} minutes of the update, rolling back to {}",
+            failed,
+            self.crash_times.len(),
+            ROLLBACK_WINDOW.as_secs() / 60,
+            previous
+        );
+        self.blacklist_version(&failed)?;
+        self.point_current_at(&previous_dir)?;
+
+        self.previous_version = None;
+        self.updated_at = None;
+        self.crash_times.clear();
+        Ok(true)
+    }
+
+    fn blacklist_path(&self) -> PathBuf {
+        self.base_dir.join("blacklist.json")
+    }
+
+    fn load_blacklist(&self) -> Vec<String> {
+        fs::read_to_string(self.blacklist_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn is_blacklisted(&self, version: &str) -> bool {
+        self.load_blacklist().iter().any(|v| v == version)
+    }
+
+    fn blacklist_version(&self, version: &str) -> Result<()> {
+        let mut blacklist = self.load_blacklist();
+        if !blacklist.iter().any(|v| v == version) {
+            blacklist.push(version.to_string());
+            fs::write(self.blacklist_path(), serde_json::to_string_pretty(&blacklist)?)?;
+        }
+        Ok(())
+    }
+
+    /// Removes `versions` from the blacklist, or every entry if none are given.
+    /// Returns the versions that were removed.
+    pub fn unblacklist(&self, versions: &[String]) -> Result<Vec<String>> {
+        let (removed, kept): (Vec<String>, Vec<String>) = self
+            .load_blacklist()
+            .into_iter()
+            .partition(|v| versions.is_empty() || versions.iter().any(|version| versions_equal(version, v)));
+
+        if !removed.is_empty() {
+            fs::write(self.blacklist_path(), serde_json::to_string_pretty(&kept)?)?;
+        }
+        Ok(removed)
+    }
+
+    fn point_current_at(&self, version_dir: &Path) -> Result<()> {
+        // Create the new link under a temporary name and rename it over `current`,
+        // so there is never a moment where `current` is missing
         #[cfg(unix)]
-        std::os::unix::fs::symlink(version_dir, &self.current_symlink)?;
+        {
+            let tmp_link = self
+                .current_symlink
+                .with_file_name(format!("current.tmp-{}", std::process::id()));
+            if fs::symlink_metadata(&tmp_link).is_ok() {
+                fs::remove_file(&tmp_link)?;
+            }
+
+            std::os::unix::fs::symlink(version_dir, &tmp_link)?;
+            if let Err(e) = fs::rename(&tmp_link, &self.current_symlink) {
+                let _ = fs::remove_file(&tmp_link);
+                return Err(e.into());
+            }
+        }
 
         Ok(())
     }
 
+    /// Points a dangling `current` link back at the newest complete install, or
+    /// removes it if there is nothing to point at.
+    pub fn repair_current_symlink(&self) -> Result<()> {
+        // `exists()` follows the link, so a dangling link reports false here
+        let is_symlink = fs::symlink_metadata(&self.current_symlink)
+            .map(|meta| meta.file_type().is_symlink())
+            .unwrap_or(false);
+        if !is_symlink || self.current_symlink.exists() {
+            return Ok(());
+        }
+
+        match self.installed_versions().first() {
+            Some(version) => {
+                warn!("current points to a missing version, switching to {}", version);
+                self.point_current_at(&self.version_dir(version))
+            }
+            None => {
+                warn!("current points to a missing version and nothing else is installed, removing it");
+                fs::remove_file(&self.current_symlink)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Complete installs under `versions_dir`, most recently installed first.
+    fn installed_versions(&self) -> Vec<String> {
+        let mut versions: Vec<String> = Vec::new();
+        for (version, _) in self.installed_dirs() {
+            if !versions.contains(&version) {
+                versions.push(version);
+            }
+        }
+        versions
+    }
+
+    /// Complete install directories with the version each holds, most recently
+    /// installed first. A version only has more than one after an interrupted
+    /// re-install.
+    fn installed_dirs(&self) -> Vec<(String, PathBuf)> {
+        let Ok(entries) = fs::read_dir(&self.versions_dir) else {
+            return Vec::new();
+        };
+
+        let mut dirs: Vec<(String, PathBuf, std::time::SystemTime)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_str()?.to_string();
+                if name.starts_with('.') || !entry.path().join(&self.bin_name).is_file() {
+                    return None;
+                }
+                let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+                Some((dir_version(&name).to_string(), entry.path(), modified))
+            })
+            .collect();
+
+        dirs.sort_by_key(|(_, _, modified)| std::cmp::Reverse(*modified));
+        dirs.into_iter().map(|(version, path, _)| (version, path)).collect()
+    }
+
+    /// The directory `current` points at, if it still exists.
+    fn current_dir(&self) -> Option<PathBuf> {
+        let target = fs::read_link(&self.current_symlink).ok()?;
+        let dir = self.versions_dir.join(target.file_name()?);
+        dir.is_dir().then_some(dir)
+    }
+
+    /// Where `version` is installed. A re-install lives under a suffixed name, so
+    /// this prefers the copy `current` uses, then the newest, and falls back to
+    /// the plain name for versions that aren't installed yet.
+    fn version_dir(&self, version: &str) -> PathBuf {
+        if let Some(dir) = self.current_dir() {
+            if dir.file_name().and_then(|name| name.to_str()).map(dir_version) == Some(version) {
+                return dir;
+            }
+        }
+
+        self.installed_dirs()
+            .into_iter()
+            .find(|(installed, _)| installed == version)
+            .map(|(_, path)| path)
+            .unwrap_or_else(|| self.versions_dir.join(version))
+    }
+
     pub fn run_miner(&self, args: &[String]) -> Result<Child> {
         let bin_path = self.current_symlink.join(&self.bin_name);
         let child = Command::new(bin_path)
@@ -890,23 +2035,78 @@ impl PackageInfo {
         Ok(())
     }
 
+    /// Ends the one-time downgrade allowance of a channel switch once an update
+    /// check on the new channel has gone through.
+    fn finish_channel_switch(&mut self) {
+        if !self.config.channel_downgrade_pending {
+            return;
+        }
+        self.config.channel_downgrade_pending = false;
+
+        // Only this flag changes on disk; command-line overrides aren't persisted
+        let result = LauncherConfig::load(&self.base_dir).and_then(|mut config| {
+            config.channel_downgrade_pending = false;
+            config.save(&self.base_dir)
+        });
+        if let Err(e) = result {
+            warn!("Failed to save the launcher config: {}", e);
+        }
+    }
+
+    /// Takes over the release information from a copy that checked for updates
+    /// without holding the lock, keeping this instance's rollback tracking.
+    fn adopt_release(&mut self, fetched: &PackageInfo) {
+        let mut fetched = fetched.clone();
+        fetched.previous_version = self.previous_version.take();
+        fetched.updated_at = self.updated_at.take();
+        fetched.crash_times = std::mem::take(&mut self.crash_times);
+        *self = fetched;
+    }
+
     pub fn start_update_watcher(
         package_info: Arc<Mutex<PackageInfo>>,
         update_notifier: Arc<Notify>,
+        relaunch_notifier: Arc<Notify>,
     ) {
         tokio::spawn(async move {
-            let mut interval = interval(Duration::from_secs(UPDATE_INTERVAL));
+            let mut first_check = true;
             loop {
-                interval.tick().await;
+                if !first_check {
+                    let delay = package_info.lock().await.next_update_delay();
+                    sleep(delay).await;
+                }
+                first_check = false;
                 info!("Checking for updates...");
 
-                let mut pi = package_info.lock().await;
+                // Work on a copy so retries and downloads don't keep the main loop
+                // from restarting a crashed miner; the lock is only taken to commit
+                let mut pi = package_info.lock().await.clone();
                 let local_version = pi.get_local_version();
 
                 if let Err(e) = pi.fetch_latest().await {
                     info!("Failed to check for updates: {}", e);
                     continue;
                 }
+                package_info.lock().await.adopt_release(&pi);
+
+                match pi.update_launcher().await {
+                    Ok(true) => {
+                        // The restarted launcher takes over checking for updates
+                        relaunch_notifier.notify_one();
+                        return;
+                    }
+                    Ok(false) => {}
+                    Err(e) => warn!("Failed to update the launcher: {}", e),
+                }
+
+                if pi.is_blacklisted(&pi.version) {
+                    info!("Version {} previously failed on this machine, skipping it", pi.version);
+                    continue;
+                }
+
+                if !pi.accepts_remote_version(local_version.as_deref()) {
+                    continue;
+                }
 
                 let version_mismatch = match local_version {
                     Some(lv) => lv != pi.version,
@@ -925,39 +2125,304 @@ impl PackageInfo {
                         info!("Missing addon files detected in background, downloading...");
                     }
                     if let Err(e) = pi.download_and_install().await {
-                        info!("Failed to download update: {}", e);
+                        info!("Failed to install update: {}", e);
                         continue;
                     }
+                    let mut pi = package_info.lock().await;
                     if let Err(e) = pi.update_symlink() {
                         info!("Failed to update symlink: {}", e);
                         continue;
                     }
+                    pi.finish_channel_switch();
                     update_notifier.notify_one();
                 } else {
                     info!("Already on the latest version.");
+                    package_info.lock().await.finish_channel_switch();
                 }
             }
         });
     }
 }
 
+/// The installed version name for a release tag. Pre-release suffixes such as
+/// `-beta.1` are kept, so those builds install next to the release they precede;
+/// any other suffix (e.g. `v0.1.5-linux`) is dropped as it always has been, so
+/// existing installs keep their directory names.
+fn release_version(tag_name: &str) -> String {
+    let tag = tag_name.trim().trim_start_matches('v');
+    match tag.split_once('-') {
+        Some((_, suffix)) if is_prerelease_suffix(suffix) => tag.to_string(),
+        Some((base, _)) => base.to_string(),
+        None => tag.to_string(),
+    }
+}
+
+/// The version an install directory holds, without the suffix a re-install adds.
+fn dir_version(name: &str) -> &str {
+    name.split_once(REINSTALL_SEPARATOR).map(|(version, _)| version).unwrap_or(name)
+}
+
+fn is_prerelease_suffix(suffix: &str) -> bool {
+    let suffix = suffix.to_lowercase();
+    PRERELEASE_TAGS.iter().any(|tag| suffix.starts_with(tag))
+}
+
+/// Parses a release version such as `v1.2.3` or `1.2` into a semver version.
+fn parse_version(version: &str) -> Option<semver::Version> {
+    let version = version.trim().trim_start_matches('v');
+    semver::Version::parse(version).ok().or_else(|| {
+        // Accept shorthand like "1.2" by padding the missing components
+        let padding = 2usize.checked_sub(version.matches('.').count())?;
+        semver::Version::parse(&format!("{}{}", version, ".0".repeat(padding))).ok()
+    })
+}
+
+fn digests_equal(a: &str, b: &str) -> bool {
+    a.strip_prefix("sha256:")
+        .unwrap_or(a)
+        .eq_ignore_ascii_case(b.strip_prefix("sha256:").unwrap_or(b))
+}
+
+fn versions_equal(a: &str, b: &str) -> bool {
+    match (parse_version(a), parse_version(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.trim_start_matches('v') == b.trim_start_matches('v'),
+    }
+}
+
+fn install_command(args: &[String]) -> Result<()> {
+    let mut bundle_path = None;
+    let mut allow_unsigned = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => {
+                bundle_path = Some(PathBuf::from(
+                    args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--from requires a bundle path"))?,
+                ));
+            }
+            "--allow-unsigned" => allow_unsigned = true,
+            other => return Err(anyhow::anyhow!("Unknown install option: {}", other)),
+        }
+    }
+
+    let bundle_path = bundle_path
+        .ok_or_else(|| anyhow::anyhow!("Usage: miner-launcher install --from <bundle> [--allow-unsigned]"))?;
+
+    let mut package_info = PackageInfo::new()?;
+    package_info.cleanup_staging()?;
+    package_info.install_from_bundle(&bundle_path, allow_unsigned)
+}
+
+fn export_command(args: &[String]) -> Result<()> {
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" | "-o" => {
+                output = Some(PathBuf::from(
+                    args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--output requires a file path"))?,
+                ));
+            }
+            other => return Err(anyhow::anyhow!("Unknown export option: {}", other)),
+        }
+    }
+
+    let mut package_info = PackageInfo::new()?;
+    if let Err(e) = package_info.load_cached_release() {
+        warn!("Ignoring cached release information: {}", e);
+    }
+
+    let output = package_info.export_bundle(output)?;
+    info!("Exported version {} to {}", package_info.get_local_version().unwrap_or_default(), output.display());
+    Ok(())
+}
+
+fn prune_command(args: &[String]) -> Result<()> {
+    let mut keep = KEEP_VERSIONS;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--keep" => {
+                keep = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--keep requires a number of versions"))?
+                    .parse()?;
+            }
+            other => return Err(anyhow::anyhow!("Unknown prune option: {}", other)),
+        }
+    }
+
+    let package_info = PackageInfo::new()?;
+    package_info.cleanup_staging()?;
+
+    let removed = package_info.prune_versions(keep)?;
+    if removed.is_empty() {
+        info!("Nothing to prune.");
+    } else {
+        info!("Removed old versions: {}", removed.join(", "));
+    }
+
+    Ok(())
+}
+
+fn unblacklist_command(args: &[String]) -> Result<()> {
+    if let Some(option) = args.iter().find(|arg| arg.starts_with('-')) {
+        return Err(anyhow::anyhow!("Unknown unblacklist option: {}", option));
+    }
+
+    let package_info = PackageInfo::new()?;
+    let removed = package_info.unblacklist(args)?;
+    if removed.is_empty() {
+        info!("No matching versions are blacklisted.");
+    } else {
+        info!("Removed from the blacklist: {}", removed.join(", "));
+    }
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     tracer::init();
 
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("install") => return install_command(&args[1..]),
+        Some("export") => return export_command(&args[1..]),
+        Some("prune") => return prune_command(&args[1..]),
+        Some("unblacklist") => return unblacklist_command(&args[1..]),
+        Some(self_update::VERSION_COMMAND) => {
+            println!("{}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        _ => {}
+    }
+
     let mut disable_update_loop = false;
     let mut no_update = false;
     let mut miner_args = Vec::new();
 
-    for arg in env::args().skip(1) {
+    let mut pin_version = None;
+    let mut allow_downgrade = false;
+    let mut channel = None;
+    let mut update_url = None;
+    let mut update_interval = None;
+    let mut serve_mirror = None;
+    let mut proxy = None;
+    let mut ca_bundles = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
         match arg.as_str() {
             "--disable-update-loop" => disable_update_loop = true,
             "--no-update" => no_update = true,
+            "--pin-version" => {
+                pin_version = Some(
+                    args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--pin-version requires a version"))?,
+                );
+            }
+            "--allow-downgrade" => allow_downgrade = true,
+            "--channel" => {
+                let name = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--channel requires stable, beta or nightly"))?;
+                channel = Some(name.parse::<Channel>()?);
+            }
+            "--update-url" => {
+                update_url = Some(
+                    args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--update-url requires a URL"))?,
+                );
+            }
+            "--update-interval" => {
+                let secs = args
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--update-interval requires a number of seconds"))?;
+                update_interval = Some(secs.parse::<u64>()?);
+            }
+            "--serve-mirror" => {
+                serve_mirror = Some(
+                    args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--serve-mirror requires an address such as 0.0.0.0:8787"))?,
+                );
+            }
+            "--proxy" => {
+                proxy = Some(
+                    args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--proxy requires a proxy URL"))?,
+                );
+            }
+            "--ca-bundle" => {
+                ca_bundles.push(PathBuf::from(
+                    args.next()
+                        .ok_or_else(|| anyhow::anyhow!("--ca-bundle requires a PEM file path"))?,
+                ));
+            }
             _ => miner_args.push(arg),
         }
     }
 
-    let package_info = PackageInfo::new()?;
+    // The channel is remembered for this install, unlike the other overrides.
+    // Moving to a more conservative channel usually means an older version, so
+    // the next successful update may downgrade, whenever that happens.
+    if let Some(channel) = channel {
+        let base_dir = PackageInfo::data_dir()?;
+        let mut config = LauncherConfig::load(&base_dir)?;
+        if config.channel != channel {
+            info!("Switching release channel from {} to {}", config.channel, channel);
+            config.channel = channel;
+            config.channel_downgrade_pending = true;
+            config.save(&base_dir)?;
+        }
+    }
+
+    let mut package_info = PackageInfo::new()?;
+    info!("Following the {} release channel", package_info.config.channel);
+    if pin_version.is_some() {
+        package_info.config.pin_version = pin_version;
+    }
+    if update_url.is_some() {
+        package_info.config.update_url = update_url;
+    }
+    if update_interval.is_some() {
+        package_info.config.update_interval_secs = update_interval;
+    }
+    if serve_mirror.is_some() {
+        package_info.config.serve_mirror = serve_mirror;
+    }
+    if proxy.is_some() {
+        package_info.config.proxy = proxy;
+    }
+    package_info.config.ca_bundles.extend(ca_bundles);
+    if package_info.config.update_url.is_some() {
+        info!("Using update endpoint {}", package_info.update_url());
+    }
+    if allow_downgrade {
+        package_info.config.allow_downgrade = true;
+    }
+    if let Err(e) = package_info.cleanup_staging() {
+        warn!("Failed to clean up leftover staging directories: {}", e);
+    }
+    if let Err(e) = package_info.repair_current_symlink() {
+        warn!("Failed to repair the current symlink: {}", e);
+    }
+    if let Err(e) = package_info.load_cached_release() {
+        warn!("Ignoring cached release information: {}", e);
+    }
+    if let Some(listen) = &package_info.config.serve_mirror {
+        let listen = listen
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid mirror address '{}': {}", listen, e))?;
+        mirror::start(package_info.clone(), listen)?;
+    }
     let package_info = Arc::new(Mutex::new(package_info));
 
     if no_update {
@@ -967,16 +2432,49 @@ async fn main() -> Result<()> {
                 "No current version installed. Please run without --no-update first."
             ));
         }
+        let missing = pi.missing_addon_files();
+        if !missing.is_empty() {
+            warn!(
+                "The installed version is missing {}, the miner may not start. Run without --no-update to repair it.",
+                missing.join(", ")
+            );
+        }
     } else {
         let mut pi = package_info.lock().await;
-        pi.ensure_latest_version().await?;
+
+        let result = pi.ensure_latest_version().await;
+        if result.is_ok() {
+            pi.finish_channel_switch();
+        }
+
+        if let Err(e) = result {
+            // An outage shouldn't stop a rig that already has a working miner;
+            // the background watcher keeps trying to update
+            match pi.get_local_version() {
+                Some(version) => {
+                    warn!("Update failed ({}), starting installed version {}", e, version);
+                    // Skip the check if the release information describes a version that failed to install
+                    let missing = if pi.version == version { pi.missing_addon_files() } else { Vec::new() };
+                    if !missing.is_empty() {
+                        warn!("Version {} is missing {}, the miner may not start", version, missing.join(", "));
+                    }
+                }
+                None => return Err(e),
+            }
+        }
+        match pi.update_launcher().await {
+            Ok(true) => return Err(self_update::reexec(&pi.launcher_exe)),
+            Ok(false) => {}
+            Err(e) => warn!("Failed to update the launcher: {}", e),
+        }
     }
 
     let restart_notifier = Arc::new(Notify::new());
     let update_notifier = Arc::new(Notify::new());
+    let relaunch_notifier = Arc::new(Notify::new());
 
     if !disable_update_loop {
-        PackageInfo::start_update_watcher(package_info.clone(), update_notifier.clone());
+        PackageInfo::start_update_watcher(package_info.clone(), update_notifier.clone(), relaunch_notifier.clone());
     }
 
     loop {
@@ -1046,11 +2544,27 @@ async fn main() -> Result<()> {
                 let _ = pi.kill_miner(&mut child_lock);
                 continue;
             }
+            _ = relaunch_notifier.notified() => {
+                info!("Stopping miner to restart the updated launcher...");
+                let mut child_lock = child.lock().await;
+                let pi = package_info.lock().await;
+                let _ = pi.kill_miner(&mut child_lock);
+                let _ = child_lock.wait().await;
+                return Err(self_update::reexec(&pi.launcher_exe));
+            }
             res = async {
                 let mut child_guard = child.lock().await;
                 child_guard.wait().await
             } => {
                 info!("Miner exited with status: {:?}. Restarting...", res);
+                if !matches!(res, Ok(status) if status.success()) {
+                    let mut pi = package_info.lock().await;
+                    match pi.record_crash() {
+                        Ok(true) => info!("Rolled back to the previous version."),
+                        Ok(false) => {}
+                        Err(e) => warn!("Failed to roll back after repeated crashes: {}", e),
+                    }
+                }
                 continue;
             }
         }
@@ -1058,3 +2572,166 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    fn scratch_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("miner-launcher-main-{}-{}", std::process::id(), test));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A release signed with the test key that offers `asset` as the binary.
+    fn signed_release(version: &str, asset: &Path) -> SignedRelease {
+        let name = asset.file_name().unwrap().to_str().unwrap();
+        let body = serde_json::json!({
+            "tag_name": format!("v{}", version),
+            "assets": [{
+                "name": name,
+                "browser_download_url": format!("https://example.com/{}", name),
+                "digest": format!("sha256:{}", download::sha256_file(asset).unwrap()),
+            }],
+            "selected_binary": name,
+        })
+        .to_string();
+        SignedRelease {
+            signature: Some(signing::test_key::sign(body.as_bytes())),
+            body,
+        }
+    }
+
+    /// Installs `asset` the way a downloaded update is, exports the result and
+    /// installs that bundle into a second data directory.
+    fn export_and_install(test: &str, asset: &Path) -> PackageInfo {
+        let base = asset.parent().unwrap();
+        let release = signed_release("1.2.3", asset);
+
+        let mut source = PackageInfo::with_base_dir(base.join("source")).unwrap();
+        source.apply_release(&release.verify().unwrap()).unwrap();
+        let staging_dir = source.create_staging_dir(test).unwrap();
+        source.write_install_files(&staging_dir, asset, None, None).unwrap();
+        source.commit_staged_install(&staging_dir).unwrap();
+        source.update_symlink().unwrap();
+        source.save_release_cache(release).unwrap();
+        let bundle = source.export_bundle(Some(base.join("bundle.zip"))).unwrap();
+
+        let mut target = PackageInfo::with_base_dir(base.join("target")).unwrap();
+        target.install_from_bundle(&bundle, false).unwrap();
+        target
+    }
+
+    /// A package offered `version` by the server, with no config file.
+    fn offered(test: &str, version: &str) -> PackageInfo {
+        let base = scratch_dir(test);
+        let mut package_info = PackageInfo::with_base_dir(base.clone()).unwrap();
+        fs::remove_dir_all(&base).unwrap();
+        package_info.version = version.to_string();
+        package_info
+    }
+
+    #[test]
+    fn parse_version_pads_shorthand_and_strips_prefix() {
+        assert_eq!(parse_version("1.2"), Some(semver::Version::new(1, 2, 0)));
+        assert_eq!(parse_version("v1"), Some(semver::Version::new(1, 0, 0)));
+        assert_eq!(parse_version(" v1.2.3 "), Some(semver::Version::new(1, 2, 3)));
+        assert_eq!(parse_version("nightly"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+    }
+
+    #[test]
+    fn parse_version_orders_prereleases_before_release() {
+        let beta1 = parse_version("1.2.0-beta.1").unwrap();
+        let beta2 = parse_version("1.2.0-beta.2").unwrap();
+        let rc = parse_version("1.2.0-rc.1").unwrap();
+        let release = parse_version("1.2").unwrap();
+        assert!(beta1 < beta2 && beta2 < rc && rc < release);
+        assert!(parse_version("1.1.9").unwrap() < beta1);
+    }
+
+    #[test]
+    fn versions_equal_compares_semver_and_falls_back_to_text() {
+        assert!(versions_equal("1.2", "v1.2.0"));
+        assert!(!versions_equal("1.2.0", "1.2.0-rc.1"));
+        assert!(versions_equal("vnightly-2024", "nightly-2024"));
+        assert!(!versions_equal("nightly-2024", "nightly-2025"));
+        assert!(!versions_equal("1.2.0", "nightly"));
+    }
+
+    #[test]
+    fn release_version_keeps_only_prerelease_suffixes() {
+        assert_eq!(release_version("v0.1.5-linux"), "0.1.5");
+        assert_eq!(release_version("v0.1.5-beta.1"), "0.1.5-beta.1");
+        assert_eq!(release_version("v0.1.5-RC1"), "0.1.5-RC1");
+        assert_eq!(release_version("0.1.5"), "0.1.5");
+    }
+
+    #[test]
+    fn accepts_remote_version_honours_pin() {
+        let mut package_info = offered("pin", "1.3.0");
+        package_info.config.pin_version = Some("v1.3".to_string());
+        assert!(package_info.accepts_remote_version(Some("1.2.0")));
+
+        package_info.config.pin_version = Some("1.2.0".to_string());
+        assert!(!package_info.accepts_remote_version(Some("1.2.0")));
+        assert!(!package_info.accepts_remote_version(None));
+    }
+
+    #[test]
+    fn accepts_remote_version_refuses_downgrade_unless_allowed() {
+        let mut package_info = offered("downgrade", "1.2.0");
+        assert!(!package_info.accepts_remote_version(Some("1.3.0")));
+        assert!(!package_info.accepts_remote_version(Some("1.2.1-beta.1")));
+        assert!(package_info.accepts_remote_version(Some("1.2.0-rc.1")));
+        assert!(package_info.accepts_remote_version(Some("1.1")));
+        assert!(package_info.accepts_remote_version(None));
+
+        package_info.config.allow_downgrade = true;
+        assert!(package_info.accepts_remote_version(Some("1.3.0")));
+
+        package_info.config.allow_downgrade = false;
+        package_info.config.channel_downgrade_pending = true;
+        assert!(package_info.accepts_remote_version(Some("1.3.0")));
+    }
+
+    #[test]
+    fn accepts_remote_version_treats_non_semver_as_update() {
+        let package_info = offered("non-semver", "nightly-2025");
+        assert!(package_info.accepts_remote_version(Some("1.3.0")));
+        assert!(package_info.accepts_remote_version(Some("nightly-2024")));
+    }
+
+    #[test]
+    fn bundle_round_trip_raw_asset() {
+        let base = scratch_dir("bundle-raw");
+        let asset = base.join("nockpool-miner-linux-x86_64");
+        fs::write(&asset, b"miner").unwrap();
+
+        let target = export_and_install("raw", &asset);
+        assert_eq!(target.get_local_version().as_deref(), Some("1.2.3"));
+        assert_eq!(fs::read(target.current_symlink.join("nockpool-miner")).unwrap(), b"miner");
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn bundle_round_trip_zip_asset() {
+        let base = scratch_dir("bundle-zip");
+        let asset = base.join("nockpool-miner-linux-x86_64.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&asset).unwrap());
+        zip.start_file("nockpool-miner", FileOptions::default()).unwrap();
+        zip.write_all(b"miner").unwrap();
+        zip.finish().unwrap();
+
+        let target = export_and_install("zip", &asset);
+        assert_eq!(target.get_local_version().as_deref(), Some("1.2.3"));
+        assert_eq!(fs::read(target.current_symlink.join("nockpool-miner")).unwrap(), b"miner");
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -0,0 +1,218 @@
+// This module lets one launcher act as an update mirror for the other rigs on
+// its LAN. Update checks are forwarded upstream and the signed responses are
+// passed through untouched, so clients still verify them against the built-in
+// key. Release files this launcher has installed are served from its
+// `versions_dir`, so each site only downloads them from the internet once.
+
+use anyhow::Result;
+use hyper::header::{CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use reqwest::Client;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_util::io::ReaderStream;
+use tracing::{info, warn};
+
+use crate::archive::{is_plain_name, ArchiveKind};
+use crate::http;
+use crate::signing::SIGNATURE_HEADER;
+use crate::PackageInfo;
+
+/// Response header telling clients where this mirror serves release files.
+pub const MIRROR_HEADER: &str = "x-nockpool-mirror";
+pub const FILES_PATH: &str = "/files/";
+
+struct MirrorState {
+    client: Client,
+    upstream_url: String,
+    /// Snapshot of the launcher's paths and settings, reloaded from disk per request.
+    package_info: PackageInfo,
+    /// Last upstream answer per kind of machine, served while upstream is down.
+    responses: Mutex<HashMap<String, CachedResponse>>,
+}
+
+#[derive(Clone)]
+struct CachedResponse {
+    body: Vec<u8>,
+    signature: Option<String>,
+}
+
+pub fn start(package_info: PackageInfo, listen: SocketAddr) -> Result<()> {
+    let state = Arc::new(MirrorState {
+        client: http::client(&package_info.config)?,
+        upstream_url: package_info.update_url().to_string(),
+        package_info,
+        responses: Mutex::new(HashMap::new()),
+    });
+
+    let server = Server::try_bind(&listen)
+        .map_err(|e| anyhow::anyhow!("Can't serve the update mirror on {}: {}", listen, e))?;
+
+    tokio::spawn(async move {
+        let make_svc = make_service_fn(move |_conn| {
+            let state = state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| handle(state.clone(), req))) }
+        });
+
+        info!("Serving update mirror on http://{}", listen);
+        if let Err(e) = server.serve(make_svc).await {
+            warn!("Update mirror stopped: {}", e);
+        }
+    });
+    Ok(())
+}
+
+async fn handle(state: Arc<MirrorState>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let path = req.uri().path().to_string();
+    let response = match (req.method(), path.as_str()) {
+        (&Method::POST, "/api/version") => proxy_version(&state, req).await,
+        (&Method::GET, path) if path.starts_with(FILES_PATH) => serve_file(&state, &path[FILES_PATH.len()..]).await,
+        _ => status_response(StatusCode::NOT_FOUND),
+    };
+    Ok(response)
+}
+
+async fn proxy_version(state: &MirrorState, req: Request<Body>) -> Response<Body> {
+    let user_agent = req.headers().get(USER_AGENT).cloned();
+    let body = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(body) => body,
+        Err(_) => return status_response(StatusCode::BAD_REQUEST),
+    };
+    let key = machine_key(&body);
+
+    let mut request = state
+        .client
+        .post(&state.upstream_url)
+        .timeout(http::read_timeout(&state.package_info.config))
+        .header(CONTENT_TYPE, "application/json")
+        .body(body.to_vec());
+    if let Some(user_agent) = user_agent {
+        request = request.header(USER_AGENT, user_agent);
+    }
+
+    match request.send().await {
+        Ok(res) if !res.status().is_server_error() => {
+            let status = res.status();
+            let signature = res
+                .headers()
+                .get(SIGNATURE_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            match res.bytes().await {
+                Ok(body) => {
+                    let response = CachedResponse {
+                        body: body.to_vec(),
+                        signature,
+                    };
+                    if status.is_success() {
+                        state.responses.lock().await.insert(key, response.clone());
+                    }
+                    release_response(status, response)
+                }
+                Err(e) => cached_or_unavailable(state, &key, &e.to_string()).await,
+            }
+        }
+        Ok(res) => cached_or_unavailable(state, &key, &res.status().to_string()).await,
+        Err(e) => cached_or_unavailable(state, &key, &e.to_string()).await,
+    }
+}
+
+async fn cached_or_unavailable(state: &MirrorState, key: &str, reason: &str) -> Response<Body> {
+    match state.responses.lock().await.get(key) {
+        Some(cached) => {
+            warn!("Upstream update server unavailable ({}), serving cached release", reason);
+            release_response(StatusCode::OK, cached.clone())
+        }
+        None => {
+            warn!("Upstream update server unavailable ({}) and nothing cached for this machine", reason);
+            status_response(StatusCode::BAD_GATEWAY)
+        }
+    }
+}
+
+fn release_response(status: StatusCode, release: CachedResponse) -> Response<Body> {
+    let mut builder = Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .header(MIRROR_HEADER, FILES_PATH);
+    if let Some(signature) = release.signature {
+        builder = builder.header(SIGNATURE_HEADER, signature);
+    }
+    builder
+        .body(Body::from(release.body))
+        .unwrap_or_else(|_| status_response(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+/// Serves `<version>/<name>` from the installed versions. A raw binary is stored
+/// as `bin_name` but requested under its release asset name. Archived binaries
+/// are kept next to what was extracted from them; clients fetch those of older
+/// installs that didn't keep them from upstream.
+async fn serve_file(state: &MirrorState, rest: &str) -> Response<Body> {
+    let Some((version, name)) = rest.split_once('/') else {
+        return status_response(StatusCode::NOT_FOUND);
+    };
+    if !is_plain_name(version) || !is_plain_name(name) {
+        return status_response(StatusCode::NOT_FOUND);
+    }
+
+    let version_dir = state.package_info.version_dir(version);
+    let mut path = version_dir.join(name);
+    if !path.is_file() {
+        let mut package_info = state.package_info.clone();
+        if package_info.load_cached_release().is_ok()
+            && package_info.version == version
+            && package_info.package_name == name
+            && ArchiveKind::from_name(name).is_none()
+        {
+            path = version_dir.join(&package_info.bin_name);
+        }
+    }
+
+    let file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(_) => return status_response(StatusCode::NOT_FOUND),
+    };
+    let len = match file.metadata().await {
+        Ok(meta) if meta.is_file() => meta.len(),
+        _ => return status_response(StatusCode::NOT_FOUND),
+    };
+
+    info!("Mirror serving {}/{}", version, name);
+    Response::builder()
+        .header(CONTENT_TYPE, "application/octet-stream")
+        .header(CONTENT_LENGTH, len)
+        .body(Body::wrap_stream(ReaderStream::new(file)))
+        .unwrap_or_else(|_| status_response(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+/// Groups update requests by the hardware they describe, so a cached answer is
+/// only ever replayed to the same kind of machine.
+fn machine_key(body: &[u8]) -> String {
+    let Ok(info) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return String::new();
+    };
+    let gpus: Vec<&serde_json::Value> = info["gpus"]
+        .as_array()
+        .map(|gpus| gpus.iter().map(|gpu| &gpu["model"]).collect())
+        .unwrap_or_default();
+
+    serde_json::json!([
+        info["os_name"],
+        info["arch"],
+        info["cpu"]["model"],
+        info["cpu"]["features"],
+        gpus,
+        info["launcher_config"],
+    ])
+    .to_string()
+}
+
+fn status_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
@@ -0,0 +1,47 @@
+// This module retries network operations that fail for transient reasons,
+// waiting exponentially longer between attempts up to a cap. Anything that
+// isn't a transient `UpdateError` is returned straight away.
+
+use anyhow::Result;
+use std::future::Future;
+use tokio::time::{sleep, Duration};
+use tracing::warn;
+
+use crate::error::UpdateError;
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+pub async fn with_backoff<T, F, Fut>(what: &str, max_attempts: u32, mut op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut delay = INITIAL_BACKOFF;
+    let mut attempt = 1;
+
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < max_attempts && is_transient(&e) => {
+                warn!(
+                    "{} failed ({}), retrying in {}s (attempt {}/{})",
+                    what,
+                    e,
+                    delay.as_secs(),
+                    attempt,
+                    max_attempts
+                );
+                sleep(delay).await;
+                delay = (delay * 2).min(MAX_BACKOFF);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+pub fn is_transient(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<UpdateError>()
+        .is_some_and(UpdateError::is_transient)
+}
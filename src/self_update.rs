@@ -0,0 +1,161 @@
+// This module lets the launcher replace its own executable. The signed release
+// response can advertise a newer launcher build; it is downloaded, checked
+// against its published digest, asked for its version, and only then renamed
+// over the running executable. The caller stops the miner and re-executes the
+// launcher with the same arguments.
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tracing::info;
+
+use crate::download;
+use crate::{parse_version, versions_equal};
+
+/// Subcommand that prints the launcher version, used to check a new build runs.
+pub const VERSION_COMMAND: &str = "launcher-version";
+
+const VERSION_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LauncherRelease {
+    pub version: String,
+    pub download_url: String,
+    // "sha256:<hex>" or a bare hex digest, required before we run anything
+    pub digest: Option<String>,
+}
+
+impl LauncherRelease {
+    /// Whether this build is newer than the running launcher.
+    pub fn is_newer(&self) -> bool {
+        match (parse_version(&self.version), parse_version(env!("CARGO_PKG_VERSION"))) {
+            (Some(remote), Some(local)) => remote > local,
+            _ => false,
+        }
+    }
+}
+
+/// Downloads `release` into `download_dir` and atomically replaces `exe` with it.
+pub async fn install(
+    client: &Client,
+    release: &LauncherRelease,
+    exe: &Path,
+    download_dir: &Path,
+    read_timeout: Duration,
+) -> Result<()> {
+    let digest = release
+        .digest
+        .as_deref()
+        .ok_or_else(|| anyhow!("Launcher {} has no published checksum, not installing it", release.version))?;
+
+    fs::create_dir_all(download_dir)?;
+    let file_name = format!("miner-launcher-{}", release.version);
+    let downloaded = download_dir.join(&file_name);
+    download::download_to_file(
+        client,
+        &release.download_url,
+        &downloaded,
+        download::DOWNLOAD_ATTEMPTS,
+        read_timeout,
+        None,
+    )
+    .await?;
+    download::verify_sha256(&downloaded, &file_name, digest)?;
+
+    // Copy next to the executable first, since a rename is only atomic within
+    // one filesystem
+    let exe_name = exe
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "miner-launcher".to_string());
+    let new_exe = exe.with_file_name(format!(".{}.new-{}", exe_name, std::process::id()));
+
+    let result = stage_executable(&downloaded, &new_exe, &release.version).await;
+    let result = match result {
+        Ok(()) => fs::rename(&new_exe, exe)
+            .map_err(|e| anyhow!("Failed to replace {}: {}", exe.display(), e)),
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        let _ = fs::remove_file(&new_exe);
+        return result;
+    }
+
+    let _ = fs::remove_file(&downloaded);
+    info!("Launcher updated to {}", release.version);
+    Ok(())
+}
+
+async fn stage_executable(downloaded: &Path, new_exe: &Path, version: &str) -> Result<()> {
+    fs::copy(downloaded, new_exe)
+        .map_err(|e| anyhow!("Failed to write {}: {}", new_exe.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(new_exe, fs::Permissions::from_mode(0o755))?;
+    }
+    fs::File::open(new_exe)?.sync_all()?;
+
+    check_version(new_exe, version).await
+}
+
+/// Runs the new build with `VERSION_COMMAND` and makes sure it is the version
+/// the release promised.
+async fn check_version(exe: &Path, version: &str) -> Result<()> {
+    let mut command = Command::new(exe);
+    command
+        .arg(VERSION_COMMAND)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let output = match tokio::time::timeout(VERSION_CHECK_TIMEOUT, command.output()).await {
+        Ok(output) => output.map_err(|e| anyhow!("Failed to run launcher {}: {}", version, e))?,
+        Err(_) => {
+            return Err(anyhow!(
+                "Launcher {} did not report its version within {} seconds",
+                version,
+                VERSION_CHECK_TIMEOUT.as_secs()
+            ))
+        }
+    };
+
+    let reported = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || !versions_equal(&reported, version) {
+        return Err(anyhow!(
+            "Launcher {} failed its version check ({}): {}",
+            version,
+            output.status,
+            if reported.is_empty() {
+                String::from_utf8_lossy(&output.stderr).trim().to_string()
+            } else {
+                reported
+            }
+        ));
+    }
+
+    Ok(())
+}
+
+/// Replaces the current process with `exe`, passing along the original
+/// arguments. Only returns if that fails.
+pub fn reexec(exe: &Path) -> anyhow::Error {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let err = std::process::Command::new(exe).args(std::env::args_os().skip(1)).exec();
+        anyhow!("Failed to restart the launcher: {}", err)
+    }
+
+    #[cfg(not(unix))]
+    {
+        anyhow!("Restarting {} in place is not supported on this platform", exe.display())
+    }
+}
@@ -0,0 +1,67 @@
+// This module verifies that release information really comes from NockPool
+// before anything in it is downloaded and executed. The update endpoint signs
+// the raw response body with Ed25519 and sends the signature in a header; the
+// matching public key is baked into the launcher at build time.
+
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::{Signature, VerifyingKey};
+
+/// Header carrying the base64-encoded Ed25519 signature of the response body.
+pub const SIGNATURE_HEADER: &str = "x-nockpool-signature";
+
+/// Base64-encoded Ed25519 public key, set via `NOCKPOOL_RELEASE_PUBLIC_KEY` at build time.
+/// `build.rs` refuses to produce a release build without a valid one.
+#[cfg(not(test))]
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("NOCKPOOL_RELEASE_PUBLIC_KEY");
+
+pub fn verify_release(body: &[u8], signature: Option<&str>) -> Result<()> {
+    let key = release_public_key()?;
+
+    let signature = signature
+        .ok_or_else(|| anyhow!("Release information is not signed, refusing to use it"))?;
+    let signature = STANDARD
+        .decode(signature.trim())
+        .map_err(|e| anyhow!("Release signature is not valid base64: {}", e))?;
+    let signature = Signature::from_slice(&signature)
+        .map_err(|e| anyhow!("Release signature is malformed: {}", e))?;
+
+    key.verify_strict(body, &signature)
+        .map_err(|_| anyhow!("Release signature does not match, refusing to use it"))
+}
+
+#[cfg(not(test))]
+fn release_public_key() -> Result<VerifyingKey> {
+    let encoded = RELEASE_PUBLIC_KEY.ok_or_else(|| {
+        anyhow!("This launcher was built without NOCKPOOL_RELEASE_PUBLIC_KEY and cannot verify releases")
+    })?;
+
+    let bytes: [u8; 32] = STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow!("Built-in release public key is not a base64 Ed25519 key"))?;
+
+    VerifyingKey::from_bytes(&bytes).map_err(|e| anyhow!("Built-in release public key is invalid: {}", e))
+}
+
+// Tests sign releases with a fixed key instead of the one baked in at build time
+#[cfg(test)]
+fn release_public_key() -> Result<VerifyingKey> {
+    Ok(test_key::signing_key().verifying_key())
+}
+
+#[cfg(test)]
+pub mod test_key {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use ed25519_dalek::{Signer, SigningKey};
+
+    pub fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7; 32])
+    }
+
+    /// Signs `body` the way the update endpoint does.
+    pub fn sign(body: &[u8]) -> String {
+        STANDARD.encode(signing_key().sign(body).to_bytes())
+    }
+}
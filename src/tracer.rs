@@ -10,7 +10,17 @@ use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{fmt, EnvFilter};
 
-use tracing::Level;
+use tracing::{info, Level};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Target used for download progress events, e.g. `RUST_LOG=info,download=warn`
+/// hides them.
+pub const DOWNLOAD_TARGET: &str = "download";
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
 
 pub fn init() {
     let fmt_layer = fmt::layer().with_ansi(true).event_format(MinimalFormatter);
@@ -111,4 +121,181 @@ where
 
         writeln!(writer)
     }
-}
\ No newline at end of file
+}
+
+/// Periodic progress for a single download: bytes, percent, rate and ETA.
+/// Events are throttled to one every `PROGRESS_INTERVAL`. When the download is
+/// part of a `CombinedProgress`, that reports for it instead.
+pub struct DownloadProgress {
+    name: String,
+    total: Option<u64>,
+    downloaded: u64,
+    resumed_at: u64,
+    started: Instant,
+    last_report: Instant,
+    combined: Option<Arc<CombinedProgress>>,
+}
+
+impl DownloadProgress {
+    pub fn new(name: &str, total: Option<u64>, resumed_at: u64, combined: Option<Arc<CombinedProgress>>) -> Self {
+        let now = Instant::now();
+        if let Some(combined) = &combined {
+            combined.update(name, resumed_at);
+        }
+        DownloadProgress {
+            name: name.to_string(),
+            total,
+            downloaded: resumed_at,
+            resumed_at,
+            started: now,
+            last_report: now,
+            combined,
+        }
+    }
+
+    pub fn advance(&mut self, bytes: u64) {
+        self.downloaded += bytes;
+        if let Some(combined) = &self.combined {
+            combined.update(&self.name, self.downloaded);
+        } else if self.last_report.elapsed() >= PROGRESS_INTERVAL {
+            self.last_report = Instant::now();
+            report(&self.name, self.downloaded, self.resumed_at, self.total, self.started);
+        }
+    }
+
+    pub fn finish(&self) {
+        info!(
+            target: DOWNLOAD_TARGET,
+            "{}: done, {} in {:.0}s",
+            self.name,
+            format_bytes(self.downloaded),
+            self.started.elapsed().as_secs_f64()
+        );
+    }
+}
+
+/// Progress across several downloads running at once, reported as a single
+/// line so the per-file events don't interleave.
+pub struct CombinedProgress {
+    total: Option<u64>,
+    started: Instant,
+    state: Mutex<CombinedState>,
+}
+
+struct CombinedState {
+    /// Bytes each file had when first seen, and has now.
+    files: HashMap<String, (u64, u64)>,
+    last_report: Instant,
+}
+
+impl CombinedProgress {
+    pub fn new(total: Option<u64>) -> Self {
+        let now = Instant::now();
+        CombinedProgress {
+            total,
+            started: now,
+            state: Mutex::new(CombinedState {
+                files: HashMap::new(),
+                last_report: now,
+            }),
+        }
+    }
+
+    /// Records that `name` now has `downloaded` bytes on disk.
+    pub fn update(&self, name: &str, downloaded: u64) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .files
+            .entry(name.to_string())
+            .or_insert((downloaded, downloaded))
+            .1 = downloaded;
+
+        if state.last_report.elapsed() >= PROGRESS_INTERVAL {
+            state.last_report = Instant::now();
+            let (resumed_at, downloaded) = state.totals();
+            let name = format!("{} files", state.files.len());
+            drop(state);
+            report(&name, downloaded, resumed_at, self.total, self.started);
+        }
+    }
+
+    pub fn finish(&self) {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (_, downloaded) = state.totals();
+        info!(
+            target: DOWNLOAD_TARGET,
+            "All {} files done, {} in {:.0}s",
+            state.files.len(),
+            format_bytes(downloaded),
+            self.started.elapsed().as_secs_f64()
+        );
+    }
+}
+
+impl CombinedState {
+    fn totals(&self) -> (u64, u64) {
+        self.files
+            .values()
+            .fold((0, 0), |(first, now), (file_first, file_now)| (first + file_first, now + file_now))
+    }
+}
+
+fn report(name: &str, downloaded: u64, resumed_at: u64, total: Option<u64>, started: Instant) {
+    // Only count bytes fetched in this session, resumed bytes came for free
+    let rate = downloaded.saturating_sub(resumed_at) as f64 / started.elapsed().as_secs_f64().max(0.001);
+
+    match total {
+        Some(total) if total > 0 => {
+            let percent = downloaded as f64 * 100.0 / total as f64;
+            let remaining = total.saturating_sub(downloaded);
+            let eta = if rate > 0.0 {
+                format_duration(remaining as f64 / rate)
+            } else {
+                "--:--".to_string()
+            };
+            info!(
+                target: DOWNLOAD_TARGET,
+                "{}: {} / {} ({:.1}%) at {}/s, ETA {}",
+                name,
+                format_bytes(downloaded),
+                format_bytes(total),
+                percent,
+                format_bytes(rate as u64),
+                eta
+            );
+        }
+        _ => {
+            info!(
+                target: DOWNLOAD_TARGET,
+                "{}: {} at {}/s",
+                name,
+                format_bytes(downloaded),
+                format_bytes(rate as u64)
+            );
+        }
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn format_duration(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}